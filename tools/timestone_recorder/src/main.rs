@@ -2,62 +2,85 @@ use anyhow::{Context, Result};
 use chrono::{DateTime, Local};
 use crossbeam_channel::{bounded, Receiver, Sender};
 use once_cell::sync::OnceCell;
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, OpenFlags};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use std::cell::RefCell;
-use std::collections::{HashMap, HashSet, hash_map::DefaultHasher};
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet, VecDeque, hash_map::DefaultHasher};
 use std::env;
 use std::ffi::c_void;
 use std::fs::{self, OpenOptions};
-use std::io::Write;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
 use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
-use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU32, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
 use windows::core::{GUID, PCWSTR, PWSTR};
-use windows::Win32::Foundation::{CloseHandle, HGLOBAL, HMODULE, HWND, LPARAM, LRESULT, POINT, RECT, STILL_ACTIVE, WPARAM};
+use windows::Win32::Foundation::{
+    CloseHandle, ERROR_PIPE_CONNECTED, FILETIME, HANDLE, HGLOBAL, HMODULE, HWND, LPARAM, LRESULT, POINT, RECT,
+    STILL_ACTIVE, UNICODE_STRING, WPARAM,
+};
 use windows::Win32::System::Com::{CoCreateInstance, CoInitializeEx, CoUninitialize, CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED};
 use windows::Win32::System::DataExchange::{
-    CloseClipboard, GetClipboardData, GetClipboardSequenceNumber, IsClipboardFormatAvailable, OpenClipboard,
+    CloseClipboard, CountClipboardFormats, EnumClipboardFormats, GetClipboardData, GetClipboardFormatNameW, GetClipboardOwner,
+    GetClipboardSequenceNumber, IsClipboardFormatAvailable, OpenClipboard,
 };
 use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::System::RemoteDesktop::{
+    WTSRegisterSessionNotification, WTSUnRegisterSessionNotification, NOTIFY_FOR_THIS_SESSION,
+};
+use windows::Win32::System::SystemInformation::GetTickCount;
 use windows::Win32::System::Memory::{GlobalLock, GlobalSize, GlobalUnlock};
+use windows::Win32::System::Pipes::{
+    ConnectNamedPipe, CreateNamedPipeW, DisconnectNamedPipe, PIPE_READMODE_BYTE, PIPE_TYPE_BYTE, PIPE_WAIT,
+};
+use windows::Win32::System::StationsAndDesktops::{
+    CloseDesktop, GetUserObjectInformationW, OpenInputDesktop, DESKTOP_CONTROL_FLAGS, DESKTOP_READOBJECTS, UOI_NAME,
+};
 use windows::Win32::System::Threading::{
-    GetCurrentThreadId, GetExitCodeProcess, OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_FORMAT,
-    PROCESS_QUERY_LIMITED_INFORMATION,
+    GetCurrentThreadId, GetExitCodeProcess, GetProcessTimes, OpenProcess, QueryFullProcessImageNameW,
+    PROCESS_NAME_FORMAT, PROCESS_QUERY_LIMITED_INFORMATION,
 };
+use windows::Wdk::System::Threading::{NtQueryInformationProcess, ProcessCommandLineInformation};
 use windows::Win32::UI::Input::KeyboardAndMouse::{
-    GetKeyboardLayout, GetKeyboardState, ToUnicodeEx, VK_BACK, VK_CONTROL, VK_LCONTROL, VK_LMENU, VK_LSHIFT, VK_LWIN,
+    GetKeyNameTextW, GetKeyboardLayout, GetLastInputInfo, LASTINPUTINFO, GetKeyboardState, MapVirtualKeyW, ToUnicodeEx, MAPVK_VSC_TO_VK_EX, VK_BACK, VK_CONTROL, VK_LCONTROL, VK_LMENU, VK_LSHIFT, VK_LWIN,
     VK_MENU, VK_RCONTROL, VK_RETURN, VK_RMENU, VK_RSHIFT, VK_RWIN, VK_SHIFT, VK_TAB,
 };
 use windows::Win32::UI::Accessibility::{
-    CUIAutomation, IUIAutomation, IUIAutomationValuePattern, SetWinEventHook, UnhookWinEvent, HWINEVENTHOOK,
-    UIA_CONTROLTYPE_ID, UIA_DocumentControlTypeId, UIA_EditControlTypeId, UIA_ValuePatternId,
+    CUIAutomation, IUIAutomation, IUIAutomationTextPattern, IUIAutomationValuePattern, SetWinEventHook, UnhookWinEvent, HWINEVENTHOOK,
+    UIA_CONTROLTYPE_ID, UIA_DocumentControlTypeId, UIA_EditControlTypeId, UIA_TextPatternId, UIA_ValuePatternId,
 };
 use windows::Win32::UI::WindowsAndMessaging::{
-    CallNextHookEx, DestroyIcon, DispatchMessageW, GetClassNameW, GetForegroundWindow, GetIconInfo, GetMessageW,
-    GetWindowRect, GetWindowTextW, GetWindowThreadProcessId, PostThreadMessageW, SetWindowsHookExW, TranslateMessage,
-    UnhookWindowsHookEx, ICONINFO, EVENT_OBJECT_LOCATIONCHANGE, EVENT_SYSTEM_FOREGROUND, HHOOK, KBDLLHOOKSTRUCT, MSG,
+    CallNextHookEx, DestroyIcon, DispatchMessageW, GetClassNameW, GetForegroundWindow, GetGUIThreadInfo, GetIconInfo,
+    GetMessageW, GetWindowLongW, GetWindowRect, GUITHREADINFO, GWL_STYLE, GetWindowTextW, GetWindowThreadProcessId, PostThreadMessageW, SetWindowsHookExW, TranslateMessage,
+    UnhookWindowsHookEx, CreateWindowExW, DefWindowProcW, DestroyWindow, RegisterClassW, HICON, HMENU, WNDCLASSW,
+    WM_ENDSESSION, WM_QUERYENDSESSION, WM_WTSSESSION_CHANGE, WS_OVERLAPPED, WTS_CONSOLE_CONNECT, WTS_CONSOLE_DISCONNECT,
+    WTS_REMOTE_CONNECT, WTS_REMOTE_DISCONNECT, WTS_SESSION_LOCK, WTS_SESSION_UNLOCK, ICONINFO, EVENT_OBJECT_LOCATIONCHANGE, EVENT_OBJECT_NAMECHANGE, EVENT_SYSTEM_FOREGROUND, HHOOK, KBDLLHOOKSTRUCT, MSG,
     MSLLHOOKSTRUCT, OBJID_WINDOW, WH_KEYBOARD_LL, WH_MOUSE_LL, WINEVENT_OUTOFCONTEXT, WM_KEYDOWN, WM_KEYUP,
     WM_LBUTTONDOWN, WM_LBUTTONUP, WM_QUIT, WM_MBUTTONDOWN, WM_MBUTTONUP, WM_MOUSEMOVE, WM_MOUSEWHEEL, WM_RBUTTONDOWN,
     WM_RBUTTONUP, WM_SYSKEYDOWN, WM_SYSKEYUP,
 };
 use windows::Win32::UI::Shell::{
-    DragQueryFileW, HDROP, IVirtualDesktopManager, SHGetFileInfoW, SHFILEINFOW, SHGFI_ICON, SHGFI_LARGEICON,
-    VirtualDesktopManager,
+    DragQueryFileW, HDROP, IVirtualDesktopManager, SHGetFileInfoW, SHGetImageList, SHFILEINFOW, SHGFI_ICON,
+    SHGFI_LARGEICON, SHGFI_SYSICONINDEX, SHIL_EXTRALARGE, SHIL_JUMBO, VirtualDesktopManager,
+};
+use windows::Win32::UI::Controls::{IImageList, ILD_TRANSPARENT};
+use windows::Win32::Storage::FileSystem::{
+    GetDiskFreeSpaceExW, ReadFile, WriteFile, FILE_FLAGS_AND_ATTRIBUTES, PIPE_ACCESS_DUPLEX,
 };
-use windows::Win32::Storage::FileSystem::FILE_FLAGS_AND_ATTRIBUTES;
 use windows::Win32::Graphics::Gdi::{
-    BITMAP, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, CreateCompatibleDC, DeleteDC, DeleteObject, GetDIBits,
-    GetMonitorInfoW, GetObjectW, MonitorFromWindow, SelectObject, DIB_RGB_COLORS, MONITORINFO, MONITORINFOEXW,
-    MONITOR_DEFAULTTONEAREST,
+    BITMAP, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, CreateCompatibleBitmap, CreateCompatibleDC, DeleteDC,
+    DeleteObject, GetDIBits, GetMonitorInfoW, GetObjectW, GetWindowDC, MonitorFromWindow, ReleaseDC, ScreenToClient,
+    SelectObject, DIB_RGB_COLORS, MONITORINFO, MONITORINFOEXW, MONITOR_DEFAULTTONEAREST,
 };
+use windows::Win32::Storage::Xps::{PrintWindow, PRINT_WINDOW_FLAGS};
 
 const APP_DIR: &str = "data\\timestone";
+const DATA_DIR_ENV: &str = "TIMESTONE_DATA_DIR";
 const DB_NAME: &str = "timestone_events.sqlite3";
 const LOCK_FILE: &str = "recorder.lock";
 const STOP_FILE: &str = "stop.signal";
@@ -66,8 +89,34 @@ const RELOAD_CONFIG_FILE: &str = "reload_config.signal";
 const CONFIG_FILE: &str = "config.json";
 const CLIPBOARD_DIR: &str = "clipboard";
 const ICONS_DIR: &str = "icons";
+const SCREENSHOTS_DIR: &str = "screenshots";
 const LOG_DIR: &str = "logs";
 const RECORDER_LOG_FILE: &str = "recorder.log";
+const CONTROL_PIPE_NAME: &str = "\\\\.\\pipe\\timestone_recorder";
+/// Bump whenever event payload shapes change so consumers can branch on `sessions.schema_version`.
+/// 2: text_input snapshot diff fields and the clipboard payload additions.
+const SCHEMA_VERSION: i64 = 2;
+/// PrintWindow flag (Windows 8.1+) that captures DirectComposition/DWM-rendered content, which
+/// plain GDI copies return black for.
+const PW_RENDERFULLCONTENT: PRINT_WINDOW_FLAGS = PRINT_WINDOW_FLAGS(2);
+const MOUSE_BUTTON_LEFT: u32 = 1;
+const MOUSE_BUTTON_RIGHT: u32 = 2;
+const MOUSE_BUTTON_MIDDLE: u32 = 4;
+const EVENT_QUEUE_CAPACITY: usize = 20_000;
+const LIVE_STREAM_CAPACITY: usize = 2_000;
+/// Above this queue depth the writer flags backpressure and low-value events are shed; the flag
+/// clears again once the queue drains below the low-water mark.
+const QUEUE_HIGH_WATER: usize = EVENT_QUEUE_CAPACITY * 8 / 10;
+const QUEUE_LOW_WATER: usize = EVENT_QUEUE_CAPACITY / 2;
+
+const SESSION_WINDOW_CLASS: &str = "TimestoneRecorderSession";
+const END_SESSION_WRITER_WAIT_MS: u64 = 4000;
+const EDIT_STYLE_PASSWORD: i32 = 0x20;
+/// Text flush reasons `text_flush_reasons` can switch off. Lifecycle, privacy, window change,
+/// staleness and max_len flushes always happen, so text is never attributed to the wrong window
+/// and the buffer stays bounded.
+const OPTIONAL_TEXT_FLUSH_REASONS: &[&str] = &["enter", "tab", "marker"];
+const DISK_CHECK_INTERVAL_MS: u64 = 30_000;
 
 const CLIPBOARD_CF_DIB: u32 = 8;
 const CLIPBOARD_CF_DIBV5: u32 = 17;
@@ -75,8 +124,11 @@ const CLIPBOARD_CF_HDROP: u32 = 15;
 const CLIPBOARD_CF_UNICODETEXT: u32 = 13;
 
 static STATE: OnceCell<Arc<RecorderState>> = OnceCell::new();
+static END_SESSION: OnceCell<EndSessionContext> = OnceCell::new();
 thread_local! {
     static UIA: RefCell<Option<IUIAutomation>> = RefCell::new(None);
+    /// Set on the thread that runs the input/WinEvent hooks, which must return quickly.
+    static HOOK_THREAD: Cell<bool> = const { Cell::new(false) };
 }
 
 fn file_logging_enabled() -> bool {
@@ -118,6 +170,7 @@ struct RecorderConfig {
     emit_mouse_move: bool,
     emit_mouse_click: bool,
     mouse_click_mode: String,
+    mouse_coord_space: String,
     emit_mouse_scroll: bool,
     capture_clipboard: bool,
     clipboard_poll_ms: u64,
@@ -127,6 +180,7 @@ struct RecorderConfig {
     window_rect_debounce_ms: u64,
     capture_raw_keys: bool,
     raw_keys_mode: String,
+    raw_keys_exclude_vks: Vec<u32>,
     suppress_raw_keys_on_shortcut: bool,
     exclude_injected_keys: bool,
     obs_video_path: Option<String>,
@@ -137,6 +191,48 @@ struct RecorderConfig {
     text_flush_ms: u64,
     max_text_len: usize,
     text_snapshot_on_idle: bool,
+    control_pipe: bool,
+    icon_recapture: bool,
+    self_screenshot_hz: f64,
+    shortcut_guard_ms: u64,
+    mouse_move_min_distance_px: u32,
+    mouse_move_gap_ms: u64,
+    detect_secure_desktop: bool,
+    heartbeat_ms: u64,
+    clipboard_text_dedupe_ms: u64,
+    min_window_area_px: u64,
+    clock_drift_threshold_ms: u64,
+    clipboard_on_copy_only: bool,
+    clipboard_copy_window_ms: u64,
+    live_stream_addr: Option<String>,
+    mouse_click_debounce_ms: u64,
+    icon_size: String,
+    text_snapshot_diff: bool,
+    capture_process_info: bool,
+    window_title_events: bool,
+    capture_profile: String,
+    store_normalized_text: bool,
+    clipboard_blocklist_titles: Vec<String>,
+    clipboard_image_max_dim: u32,
+    snapshot_active_hz: Option<f64>,
+    snapshot_idle_hz: Option<f64>,
+    snapshot_idle_after_ms: u64,
+    capture_selection_on_copy: bool,
+    detect_session_changes: bool,
+    clipboard_hash_history: usize,
+    clipboard_hash_algorithm: String,
+    suppress_key_repeats: bool,
+    min_free_mb: u64,
+    low_disk_auto_pause: bool,
+    capture_keystroke_timing: bool,
+    capture_unknown_clipboard: bool,
+    mouse_move_buttons: bool,
+    scroll_coalesce_ms: u64,
+    exclude_event_types: Vec<String>,
+    max_payload_bytes: usize,
+    safe_text_fallback: String,
+    text_flush_reasons: Vec<String>,
+    preserialize_events: bool,
 }
 
 impl Default for RecorderConfig {
@@ -148,6 +244,7 @@ impl Default for RecorderConfig {
             emit_mouse_move: false,
             emit_mouse_click: true,
             mouse_click_mode: "down".to_string(),
+            mouse_coord_space: "screen".to_string(),
             emit_mouse_scroll: false,
             capture_clipboard: true,
             clipboard_poll_ms: 250,
@@ -157,6 +254,7 @@ impl Default for RecorderConfig {
             window_rect_debounce_ms: 300,
             capture_raw_keys: true,
             raw_keys_mode: "down".to_string(),
+            raw_keys_exclude_vks: Vec::new(),
             suppress_raw_keys_on_shortcut: true,
             exclude_injected_keys: true,
             obs_video_path: None,
@@ -171,6 +269,48 @@ impl Default for RecorderConfig {
             text_flush_ms: 1500,
             max_text_len: 2000,
             text_snapshot_on_idle: false,
+            control_pipe: false,
+            icon_recapture: true,
+            self_screenshot_hz: 0.0,
+            shortcut_guard_ms: 0,
+            mouse_move_min_distance_px: 0,
+            mouse_move_gap_ms: 1000,
+            detect_secure_desktop: false,
+            heartbeat_ms: 0,
+            clipboard_text_dedupe_ms: 0,
+            min_window_area_px: 0,
+            clock_drift_threshold_ms: 2000,
+            clipboard_on_copy_only: false,
+            clipboard_copy_window_ms: 1500,
+            live_stream_addr: None,
+            mouse_click_debounce_ms: 0,
+            icon_size: "large".to_string(),
+            text_snapshot_diff: false,
+            capture_process_info: false,
+            window_title_events: false,
+            capture_profile: "full".to_string(),
+            store_normalized_text: false,
+            clipboard_blocklist_titles: Vec::new(),
+            clipboard_image_max_dim: 0,
+            snapshot_active_hz: None,
+            snapshot_idle_hz: None,
+            snapshot_idle_after_ms: 60000,
+            capture_selection_on_copy: false,
+            detect_session_changes: true,
+            clipboard_hash_history: 1,
+            clipboard_hash_algorithm: "default".to_string(),
+            suppress_key_repeats: false,
+            min_free_mb: 0,
+            low_disk_auto_pause: false,
+            capture_keystroke_timing: false,
+            capture_unknown_clipboard: false,
+            mouse_move_buttons: false,
+            scroll_coalesce_ms: 200,
+            exclude_event_types: Vec::new(),
+            max_payload_bytes: 1_048_576,
+            safe_text_fallback: "none".to_string(),
+            text_flush_reasons: OPTIONAL_TEXT_FLUSH_REASONS.iter().map(|reason| reason.to_string()).collect(),
+            preserialize_events: false,
         }
     }
 }
@@ -184,6 +324,7 @@ struct CliOverrides {
     obs_video_path: Option<String>,
     obs_video_dir: Option<String>,
     safe_text_only: Option<bool>,
+    resume_latest: bool,
 }
 
 #[derive(Clone)]
@@ -192,43 +333,108 @@ struct SessionInfo {
     start_wall_ms: i64,
     start_wall_iso: String,
     obs_video_path: Option<String>,
+    schema_version: i64,
 }
 
 struct TextBuffer {
     text: String,
     last_ts_ms: i64,
+    typed_chars: u32,
+    backspaces: u32,
+    /// Gaps between consecutive keystrokes, filled only when `capture_keystroke_timing` is on.
+    key_intervals_ms: Vec<i64>,
+    /// A high surrogate from a key event whose low half (e.g. the second VK_PACKET of an emoji)
+    /// has not arrived yet.
+    pending_high_surrogate: Option<u16>,
+}
+
+impl TextBuffer {
+    /// Only text makes a flush worthwhile; counts from backspace-only runs ride along with the next one.
+    fn has_pending(&self) -> bool {
+        !self.text.is_empty()
+    }
 }
 
 struct RecorderState {
     session_id: String,
-    sender: Sender<EventRecord>,
+    sender: Sender<QueuedEvent>,
+    dropped_events: AtomicU64,
+    shed_events: AtomicU64,
+    queue_near_full: Arc<AtomicBool>,
     start_instant: Instant,
+    mono_offset_ms: i64,
     mouse_move_interval_ms: i64,
     last_mouse_move_ms: AtomicI64,
+    last_mouse_move_pos: Mutex<Option<(i32, i32)>>,
+    mouse_move_min_distance_px: i64,
+    mouse_move_gap_ms: i64,
+    mouse_move_buttons: bool,
+    /// Bitmask of `MOUSE_BUTTON_*` currently held, kept from every button message even when
+    /// click events are filtered out.
+    mouse_buttons_down: AtomicU32,
     paused: AtomicBool,
     capture_raw_keys: bool,
     raw_keys_mode: RawKeysMode,
+    raw_keys_exclude_vks: HashSet<u32>,
     suppress_raw_keys_on_shortcut: bool,
+    suppress_key_repeats: bool,
+    capture_keystroke_timing: bool,
     exclude_injected_keys: bool,
     emit_mouse_move: AtomicBool,
     emit_mouse_click: AtomicBool,
     emit_mouse_scroll: AtomicBool,
     mouse_click_mode: MouseClickMode,
+    mouse_click_debounce_ms: i64,
+    last_button_down_ms: [AtomicI64; 3],
+    bounced_button_up: [AtomicBool; 3],
+    mouse_coord_space: MouseCoordSpace,
     pressed_keys: Mutex<HashSet<u32>>,
+    shortcut_guard_ms: i64,
+    last_chord_ms: AtomicI64,
+    /// A non-modifier key went down while Ctrl/Alt/Win was held; cleared once they are all up.
+    chord_modifiers_held: AtomicBool,
     safe_text_only: bool,
     allowlist_processes: Vec<String>,
     blocklist_processes: Vec<String>,
+    clipboard_blocklist_titles: Vec<String>,
+    clipboard_image_max_dim: u32,
     text_flush_ms: i64,
     max_text_len: usize,
     text_buffer: Mutex<TextBuffer>,
     text_snapshot_on_idle: bool,
+    text_snapshot_diff: bool,
+    store_normalized_text: bool,
+    last_final_text: Mutex<HashMap<String, String>>,
     clipboard_dedupe_window_ms: i64,
-    last_clipboard_hash: Mutex<Option<ClipboardHash>>,
-    app_icon_cache: Mutex<HashMap<String, String>>,
+    last_clipboard_hash: Mutex<VecDeque<ClipboardHash>>,
+    clipboard_hash_history: usize,
+    hash_algorithm: HashAlgorithm,
+    capture_unknown_clipboard: bool,
+    clipboard_text_dedupe_ms: i64,
+    clipboard_on_copy_only: bool,
+    clipboard_copy_window_ms: i64,
+    clipboard_expected_until_ms: AtomicI64,
+    selection_requests: Option<Sender<()>>,
+    last_clipboard_text_hash: Mutex<Option<ClipboardHash>>,
+    app_icon_cache: Mutex<HashMap<String, CachedIcon>>,
     icons_dir: PathBuf,
+    icon_recapture: bool,
+    icon_size: IconSize,
     window_rect_debounce_ms: i64,
+    min_window_area_px: i64,
     window_tracker: Mutex<WindowTracker>,
+    capture_process_info: bool,
+    window_title_events: bool,
+    seen_processes: Mutex<HashSet<(u32, i64)>>,
     scroll_buffer: Mutex<Option<ScrollBuffer>>,
+    scroll_coalesce_ms: i64,
+    exclude_event_types: HashSet<String>,
+    max_payload_bytes: usize,
+    safe_text_fallback: SafeTextFallback,
+    uia_unavailable_reported: AtomicBool,
+    text_flush_reasons: HashSet<String>,
+    preserialize_events: bool,
+    live_stream_enabled: bool,
     last_virtual_desktop_id: Mutex<Option<String>>,
 }
 
@@ -246,6 +452,46 @@ enum MouseClickMode {
     Both,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum HashAlgorithm {
+    Default,
+    Fnv1a,
+}
+
+impl HashAlgorithm {
+    fn name(self) -> &'static str {
+        match self {
+            HashAlgorithm::Default => "default",
+            HashAlgorithm::Fnv1a => "fnv1a",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CaptureProfile {
+    Full,
+    FocusOnly,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SafeTextFallback {
+    None,
+    BlocklistOnly,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum IconSize {
+    Large,
+    ExtraLarge,
+    Jumbo,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum MouseCoordSpace {
+    Screen,
+    WindowClient,
+}
+
 #[derive(Serialize, Clone, PartialEq)]
 struct RectInfo {
     left: i32,
@@ -282,6 +528,13 @@ struct ClipboardImage {
     path: String,
     width: i32,
     height: i32,
+    original_width: i32,
+    original_height: i32,
+}
+
+struct CachedIcon {
+    path: String,
+    modified_ms: Option<i64>,
 }
 
 struct ClipboardHash {
@@ -297,13 +550,14 @@ struct ScrollBuffer {
     ticks: i32,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize)]
 struct EventRecord {
     session_id: String,
     ts_wall_ms: i64,
     ts_mono_ms: i64,
     event_type: String,
     process_name: Option<String>,
+    process_id: Option<u32>,
     window_title: Option<String>,
     window_class: Option<String>,
     window_rect: Option<RectInfo>,
@@ -311,8 +565,43 @@ struct EventRecord {
     payload: Value,
 }
 
+/// JSON text for the events table's TEXT columns.
+struct SerializedColumns {
+    window_rect: Option<String>,
+    mouse: Option<String>,
+    payload: String,
+}
+
+impl SerializedColumns {
+    fn from_event(event: &EventRecord) -> Self {
+        SerializedColumns {
+            window_rect: event
+                .window_rect
+                .as_ref()
+                .and_then(|rect| serde_json::to_string(rect).ok()),
+            mouse: event
+                .mouse
+                .as_ref()
+                .and_then(|m| serde_json::to_string(m).ok()),
+            payload: serde_json::to_string(&event.payload).unwrap_or_else(|_| "{}".to_string()),
+        }
+    }
+}
+
+/// What travels to the writer. With `preserialize_events` the columns are serialized by
+/// emit_event on the capturing thread (hook threads excepted), leaving the writer to bind and
+/// insert; the payload is then dropped unless the live stream still needs it.
+struct QueuedEvent {
+    event: EventRecord,
+    columns: Option<SerializedColumns>,
+    /// `(typed_chars, backspaces)` of a text_input, for typing_stats.
+    typing_counts: Option<(i64, i64)>,
+}
+
 fn main() -> Result<()> {
-    let mut args = env::args().skip(1);
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    apply_data_dir_arg(&mut args);
+    let mut args = args.into_iter();
     match args.next().as_deref() {
         Some("start") => {
             let overrides = parse_start_args(args);
@@ -331,7 +620,39 @@ fn main() -> Result<()> {
             stop_recorder()?;
         }
         Some("status") => {
-            print_status()?;
+            print_status(args.any(|arg| arg == "--json"))?;
+        }
+        Some("config") => {
+            let args: Vec<String> = args.collect();
+            if args.iter().any(|arg| arg == "--effective") {
+                print_effective_config(parse_start_args(args.into_iter()))?;
+            } else {
+                print_usage();
+            }
+        }
+        Some("export-csv") => {
+            export_csv(parse_export_args(args))?;
+        }
+        Some("export-bundle") => {
+            export_bundle(parse_export_args(args))?;
+        }
+        Some("tag") => {
+            tag_session(parse_tag_args(args))?;
+        }
+        Some("sessions") => {
+            list_sessions()?;
+        }
+        Some("compare") => {
+            compare_sessions(parse_compare_args(args))?;
+        }
+        Some("reconstruct-text") => {
+            reconstruct_text(parse_export_args(args))?;
+        }
+        Some("doctor") => {
+            run_doctor()?;
+        }
+        Some("compact") => {
+            compact_db()?;
         }
         _ => {
             print_usage();
@@ -342,14 +663,627 @@ fn main() -> Result<()> {
 
 fn print_usage() {
     println!("timestone_recorder");
-    println!("Usage:");
+    println!("Usage (any command accepts --data-dir PATH, or set TIMESTONE_DATA_DIR):");
     println!("  timestone_recorder start [--config PATH] [--safe-text|--no-safe-text] [--raw-keys]");
     println!("                           [--mouse-hz N] [--snapshot-hz N] [--obs-video PATH] [--obs-dir PATH]");
+    println!("                           [--resume-latest]");
     println!("  timestone_recorder pause");
     println!("  timestone_recorder resume");
     println!("  timestone_recorder toggle");
     println!("  timestone_recorder stop");
-    println!("  timestone_recorder status");
+    println!("  timestone_recorder status [--json]");
+    println!("  timestone_recorder config --effective [--config PATH] [start overrides...]");
+    println!("  timestone_recorder export-csv --session ID|latest --out PATH");
+    println!("  timestone_recorder reconstruct-text --session ID|latest --out PATH");
+    println!("  timestone_recorder doctor");
+    println!("  timestone_recorder export-bundle --session ID|latest --out PATH");
+    println!("  timestone_recorder tag --session ID|latest [--add a,b] [--remove a,b] [--list]");
+    println!("  timestone_recorder sessions");
+    println!("  timestone_recorder compare --a ID|latest --b ID|latest");
+    println!("  timestone_recorder compact");
+}
+
+/// Strips a global `--data-dir PATH` from the arguments and exports it as `TIMESTONE_DATA_DIR`,
+/// which `ensure_app_dir` reads and any child process inherits.
+fn apply_data_dir_arg(args: &mut Vec<String>) {
+    let Some(index) = args.iter().position(|arg| arg == "--data-dir") else {
+        return;
+    };
+    args.remove(index);
+    if index < args.len() {
+        env::set_var(DATA_DIR_ENV, args.remove(index));
+    }
+}
+
+#[derive(Default)]
+struct ExportArgs {
+    session_id: Option<String>,
+    out_path: Option<PathBuf>,
+}
+
+fn parse_export_args(mut args: impl Iterator<Item = String>) -> ExportArgs {
+    let mut export = ExportArgs::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--session" => {
+                export.session_id = args.next();
+            }
+            "--out" => {
+                export.out_path = args.next().map(PathBuf::from);
+            }
+            _ => {}
+        }
+    }
+    export
+}
+
+/// Maps the `latest` shorthand to the most recently started session; other values pass through.
+fn resolve_session_id(db_path: &Path, value: &str) -> Result<String> {
+    if !value.eq_ignore_ascii_case("latest") {
+        return Ok(value.to_string());
+    }
+    let conn = open_db_read_only(db_path)?;
+    conn.query_row(
+        "SELECT session_id FROM sessions ORDER BY start_wall_ms DESC LIMIT 1",
+        [],
+        |row| row.get::<_, String>(0),
+    )
+    .context("No sessions recorded yet")
+}
+
+/// For commands that only read; unlike `Connection::open` this never creates an empty database.
+fn open_db_read_only(db_path: &Path) -> Result<Connection> {
+    if !db_path.exists() {
+        anyhow::bail!("No database at {}", db_path.display());
+    }
+    Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_ONLY).context("Failed to open events database")
+}
+
+/// Flattens a session's events into spreadsheet-friendly columns, keeping the raw payload JSON as
+/// the last column.
+fn export_csv(export: ExportArgs) -> Result<()> {
+    let (Some(session_id), Some(out_path)) = (export.session_id, export.out_path) else {
+        print_usage();
+        return Ok(());
+    };
+    let db_path = ensure_app_dir()?.join(DB_NAME);
+    let session_id = resolve_session_id(&db_path, &session_id)?;
+    let conn = open_db_read_only(&db_path)?;
+    let mut stmt = conn.prepare(
+        "SELECT ts_wall_ms, event_type, process_name, window_title, mouse, payload FROM events
+         WHERE session_id = ?1 ORDER BY ts_mono_ms, id",
+    )?;
+    let mut rows = stmt.query(params![session_id])?;
+
+    let file = fs::File::create(&out_path).context("Failed to create csv file")?;
+    let mut writer = std::io::BufWriter::new(file);
+    // The BOM makes Excel read the file as UTF-8 rather than the ANSI code page.
+    writer.write_all(b"\xEF\xBB\xBF")?;
+    writer.write_all(b"time,ts_wall_ms,event_type,process,title,x,y,button,key,payload\r\n")?;
+    let mut count = 0u64;
+    while let Some(row) = rows.next()? {
+        let ts_wall_ms: i64 = row.get(0)?;
+        let event_type: String = row.get(1)?;
+        let process_name: Option<String> = row.get(2)?;
+        let window_title: Option<String> = row.get(3)?;
+        let mouse: Value = row
+            .get::<_, Option<String>>(4)?
+            .and_then(|mouse| serde_json::from_str(&mouse).ok())
+            .unwrap_or(Value::Null);
+        let payload: Option<String> = row.get(5)?;
+        let key = payload
+            .as_deref()
+            .and_then(|payload| serde_json::from_str::<Value>(payload).ok())
+            .and_then(|payload| payload.get("key").and_then(Value::as_str).map(str::to_string));
+        let time = DateTime::<Local>::from(UNIX_EPOCH + Duration::from_millis(ts_wall_ms.max(0) as u64));
+        let mouse_field = |name: &str| match mouse.get(name) {
+            Some(Value::String(value)) => value.clone(),
+            Some(Value::Null) | None => String::new(),
+            Some(value) => value.to_string(),
+        };
+        let fields = [
+            time.to_rfc3339(),
+            ts_wall_ms.to_string(),
+            event_type,
+            process_name.unwrap_or_default(),
+            window_title.unwrap_or_default(),
+            mouse_field("x"),
+            mouse_field("y"),
+            mouse_field("button"),
+            key.unwrap_or_default(),
+            payload.unwrap_or_default(),
+        ];
+        let line: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        writer.write_all(line.join(",").as_bytes())?;
+        writer.write_all(b"\r\n")?;
+        count += 1;
+    }
+    writer.flush()?;
+    println!("Exported {} events to {}", count, out_path.display());
+    Ok(())
+}
+
+/// RFC 4180 quoting: fields containing a comma, quote or line break are wrapped in quotes with
+/// embedded quotes doubled.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Concatenates a session's text_input events into one document, with a header whenever the
+/// typing moves to another window. A UIA final_text snapshot wins over the buffered keystrokes.
+fn reconstruct_text(export: ExportArgs) -> Result<()> {
+    let (Some(session_id), Some(out_path)) = (export.session_id, export.out_path) else {
+        print_usage();
+        return Ok(());
+    };
+    let db_path = ensure_app_dir()?.join(DB_NAME);
+    let session_id = resolve_session_id(&db_path, &session_id)?;
+    let conn = open_db_read_only(&db_path)?;
+    let mut stmt = conn.prepare(
+        "SELECT ts_wall_ms, process_name, window_title, payload FROM events
+         WHERE session_id = ?1 AND event_type = 'text_input' ORDER BY ts_mono_ms, id",
+    )?;
+    let rows = stmt.query_map(params![session_id], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, Option<String>>(1)?,
+            row.get::<_, Option<String>>(2)?,
+            row.get::<_, Option<String>>(3)?,
+        ))
+    })?;
+
+    let mut document = String::new();
+    let mut current_window: Option<(Option<String>, Option<String>)> = None;
+    // Where the current window's latest UIA snapshot starts; a newer snapshot replaces it.
+    let mut snapshot_start: Option<usize> = None;
+    let mut count = 0u64;
+    for row in rows {
+        let (ts_wall_ms, process_name, window_title, payload) = row?;
+        let payload: Value = payload
+            .as_deref()
+            .and_then(|payload| serde_json::from_str(payload).ok())
+            .unwrap_or(Value::Null);
+        let final_text = payload.get("final_text").and_then(Value::as_str);
+        let text = final_text
+            .or_else(|| payload.get("text").and_then(Value::as_str))
+            .unwrap_or("");
+        if text.is_empty() {
+            continue;
+        }
+        let window = (process_name, window_title);
+        if current_window.as_ref() != Some(&window) {
+            let started = DateTime::<Local>::from(UNIX_EPOCH + Duration::from_millis(ts_wall_ms.max(0) as u64));
+            if !document.is_empty() {
+                document.push_str("\n\n");
+            }
+            document.push_str(&format!(
+                "## {} - {} ({})\n\n",
+                window.0.as_deref().map(normalize_process_name).unwrap_or_default(),
+                window.1.as_deref().unwrap_or(""),
+                started.to_rfc3339()
+            ));
+            current_window = Some(window);
+            snapshot_start = None;
+        }
+        if final_text.is_some() {
+            let start = *snapshot_start.get_or_insert(document.len());
+            document.truncate(start);
+            document.push_str(text);
+        } else {
+            document.push_str(text);
+            match payload.get("reason").and_then(Value::as_str) {
+                Some("enter") => document.push('\n'),
+                Some("tab") => document.push('\t'),
+                _ => {}
+            }
+        }
+        count += 1;
+    }
+    fs::write(&out_path, document).context("Failed to write reconstructed text")?;
+    println!("Reconstructed {} text events to {}", count, out_path.display());
+    Ok(())
+}
+
+/// Text-only tables copied by export-bundle; ones the database doesn't have are skipped.
+const BUNDLE_TABLES: &[&str] = &["sessions", "events", "session_tags", "segment_transcriptions", "event_ocr"];
+
+fn export_bundle(export: ExportArgs) -> Result<()> {
+    let (Some(session_id), Some(out_path)) = (export.session_id, export.out_path) else {
+        print_usage();
+        return Ok(());
+    };
+    if out_path.exists() {
+        anyhow::bail!("{} already exists", out_path.display());
+    }
+    let db_path = ensure_app_dir()?.join(DB_NAME);
+    let session_id = resolve_session_id(&db_path, &session_id)?;
+    let source = open_db_read_only(&db_path)?;
+    let known: bool = source.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sessions WHERE session_id = ?1)",
+        params![session_id],
+        |row| row.get(0),
+    )?;
+    if !known {
+        anyhow::bail!("No session {session_id} in {}", db_path.display());
+    }
+
+    // Built beside the target and renamed into place, so a failure never leaves a partial bundle
+    // that the exists() guard would then refuse to overwrite.
+    let mut partial_name = out_path.file_name().unwrap_or_default().to_os_string();
+    partial_name.push(".partial");
+    let partial_path = out_path.with_file_name(partial_name);
+    let _ = fs::remove_file(&partial_path);
+    if let Err(err) = write_bundle(&source, &partial_path, &session_id) {
+        let _ = fs::remove_file(&partial_path);
+        return Err(err);
+    }
+    fs::rename(&partial_path, &out_path).context("Failed to move bundle into place")?;
+    println!("Bundle written to {}", out_path.display());
+    Ok(())
+}
+
+fn write_bundle(source: &Connection, bundle_path: &Path, session_id: &str) -> Result<()> {
+    let mut bundle = Connection::open(bundle_path).context("Failed to create bundle database")?;
+    let tx = bundle.transaction()?;
+    for table in BUNDLE_TABLES {
+        let schema: Option<String> = source
+            .query_row(
+                "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?1",
+                params![table],
+                |row| row.get(0),
+            )
+            .ok();
+        let Some(schema) = schema else {
+            continue;
+        };
+        let columns = table_columns(source, table)?;
+        let filter = if columns.iter().any(|column| column == "session_id") {
+            "session_id = ?1"
+        } else if columns.iter().any(|column| column == "event_id") {
+            "event_id IN (SELECT id FROM events WHERE session_id = ?1)"
+        } else {
+            println!("Skipping {table}: no session_id or event_id column");
+            continue;
+        };
+        tx.execute_batch(&schema)?;
+        let mut select = source.prepare(&format!("SELECT * FROM {table} WHERE {filter}"))?;
+        let width = select.column_count();
+        let mut insert = tx.prepare(&format!("INSERT INTO {table} VALUES ({})", vec!["?"; width].join(", ")))?;
+        let mut rows = select.query(params![session_id])?;
+        let mut copied = 0u64;
+        while let Some(row) = rows.next()? {
+            let values = (0..width)
+                .map(|index| row.get::<_, rusqlite::types::Value>(index))
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            insert.execute(rusqlite::params_from_iter(values))?;
+            copied += 1;
+        }
+        println!("{table}: {copied} rows");
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+fn table_columns(conn: &Connection, table: &str) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({table})"))?;
+    let columns = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(columns)
+}
+
+#[derive(Default)]
+struct TagArgs {
+    session_id: Option<String>,
+    add: Vec<String>,
+    remove: Vec<String>,
+    list: bool,
+}
+
+fn parse_tag_args(mut args: impl Iterator<Item = String>) -> TagArgs {
+    let mut tag = TagArgs::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--session" => {
+                tag.session_id = args.next();
+            }
+            "--add" => {
+                if let Some(value) = args.next() {
+                    tag.add.extend(value.split(',').map(str::to_string));
+                }
+            }
+            "--remove" => {
+                if let Some(value) = args.next() {
+                    tag.remove.extend(value.split(',').map(str::to_string));
+                }
+            }
+            "--list" => {
+                tag.list = true;
+            }
+            _ => {}
+        }
+    }
+    tag
+}
+
+/// Tags follow the process list rules (trimmed, lowercased) and are deduped in order.
+fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    normalize_process_list(tags)
+        .into_iter()
+        .filter(|tag| seen.insert(tag.clone()))
+        .collect()
+}
+
+fn tag_session(tag: TagArgs) -> Result<()> {
+    let Some(session_id) = tag.session_id else {
+        print_usage();
+        return Ok(());
+    };
+    let db_path = ensure_app_dir()?.join(DB_NAME);
+    let session_id = resolve_session_id(&db_path, &session_id)?;
+    let conn = Connection::open(&db_path).context("Failed to open events database")?;
+    init_db(&conn)?;
+    let exists: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sessions WHERE session_id = ?1)",
+        params![session_id],
+        |row| row.get(0),
+    )?;
+    if !exists {
+        anyhow::bail!("Unknown session {session_id}");
+    }
+    for label in normalize_tags(tag.add) {
+        conn.execute(
+            "INSERT OR IGNORE INTO session_tags (session_id, tag) VALUES (?1, ?2)",
+            params![session_id, label],
+        )?;
+    }
+    for label in normalize_tags(tag.remove) {
+        conn.execute(
+            "DELETE FROM session_tags WHERE session_id = ?1 AND tag = ?2",
+            params![session_id, label],
+        )?;
+    }
+    if tag.list {
+        println!("{} {}", session_id, session_tags(&conn, &session_id)?.join(","));
+    }
+    Ok(())
+}
+
+fn session_tags(conn: &Connection, session_id: &str) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare("SELECT tag FROM session_tags WHERE session_id = ?1 ORDER BY tag")?;
+    let tags = stmt
+        .query_map(params![session_id], |row| row.get::<_, String>(0))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(tags)
+}
+
+fn list_sessions() -> Result<()> {
+    let db_path = ensure_app_dir()?.join(DB_NAME);
+    let conn = open_db_read_only(&db_path)?;
+    // Databases from before tagging have no session_tags table, and this must not create it.
+    let sql = if table_columns(&conn, "session_tags")?.is_empty() {
+        "SELECT session_id, start_wall_iso, NULL FROM sessions ORDER BY start_wall_ms DESC"
+    } else {
+        "SELECT s.session_id, s.start_wall_iso, GROUP_CONCAT(t.tag, ',')
+         FROM sessions s LEFT JOIN session_tags t ON t.session_id = s.session_id
+         GROUP BY s.session_id ORDER BY s.start_wall_ms DESC"
+    };
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, Option<String>>(1)?,
+            row.get::<_, Option<String>>(2)?,
+        ))
+    })?;
+    for row in rows {
+        let (session_id, start_iso, tags) = row?;
+        println!(
+            "{}  {}  {}",
+            session_id,
+            start_iso.unwrap_or_default(),
+            tags.unwrap_or_default()
+        );
+    }
+    Ok(())
+}
+
+/// Rebuilds the database file so space freed by deletions goes back to the OS. VACUUM needs the
+/// database to itself, so this refuses to run while a recorder holds the lock.
+fn compact_db() -> Result<()> {
+    let base_dir = ensure_app_dir()?;
+    if base_dir.join(LOCK_FILE).exists() {
+        anyhow::bail!("Recorder is running (lock file present); stop it before compacting.");
+    }
+    let db_path = base_dir.join(DB_NAME);
+    if !db_path.exists() {
+        println!("No database at {}.", db_path.display());
+        return Ok(());
+    }
+    let before = db_file_size(&db_path);
+    let conn = Connection::open(&db_path).context("Failed to open events database")?;
+    println!("Checkpointing WAL...");
+    conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE);")
+        .context("WAL checkpoint failed")?;
+    println!("Running VACUUM...");
+    conn.execute_batch("VACUUM;").context("VACUUM failed")?;
+    println!("Running REINDEX...");
+    conn.execute_batch("REINDEX;").context("REINDEX failed")?;
+    drop(conn);
+    let after = db_file_size(&db_path);
+    println!(
+        "Compacted {}: {:.1} MB -> {:.1} MB",
+        db_path.display(),
+        before as f64 / (1024.0 * 1024.0),
+        after as f64 / (1024.0 * 1024.0)
+    );
+    log_line(&format!("Database compacted: {before} -> {after} bytes"));
+    Ok(())
+}
+
+/// Size of the database including its WAL, which holds recent writes until a checkpoint.
+fn db_file_size(db_path: &Path) -> u64 {
+    let wal_path = PathBuf::from(format!("{}-wal", db_path.display()));
+    [db_path.to_path_buf(), wal_path]
+        .iter()
+        .filter_map(|path| fs::metadata(path).ok())
+        .map(|meta| meta.len())
+        .sum()
+}
+
+#[derive(Default)]
+struct CompareArgs {
+    session_a: Option<String>,
+    session_b: Option<String>,
+}
+
+fn parse_compare_args(mut args: impl Iterator<Item = String>) -> CompareArgs {
+    let mut compare = CompareArgs::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--a" => {
+                compare.session_a = args.next();
+            }
+            "--b" => {
+                compare.session_b = args.next();
+            }
+            _ => {}
+        }
+    }
+    compare
+}
+
+fn compare_sessions(compare: CompareArgs) -> Result<()> {
+    let (Some(session_a), Some(session_b)) = (compare.session_a, compare.session_b) else {
+        print_usage();
+        return Ok(());
+    };
+    let db_path = ensure_app_dir()?.join(DB_NAME);
+    let session_a = resolve_session_id(&db_path, &session_a)?;
+    let session_b = resolve_session_id(&db_path, &session_b)?;
+    let conn = open_db_read_only(&db_path)?;
+    let usage_a = foreground_time_by_process(&conn, &session_a)?;
+    let usage_b = foreground_time_by_process(&conn, &session_b)?;
+
+    let mut processes: Vec<&String> = usage_a
+        .keys()
+        .chain(usage_b.keys())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    let minutes = |usage: &HashMap<String, i64>, process: &str| {
+        usage.get(process).copied().unwrap_or(0) as f64 / 60_000.0
+    };
+    processes.sort_by(|left, right| {
+        let left_max = minutes(&usage_a, left).max(minutes(&usage_b, left));
+        let right_max = minutes(&usage_a, right).max(minutes(&usage_b, right));
+        right_max.total_cmp(&left_max).then_with(|| left.cmp(right))
+    });
+    println!("A: {session_a}");
+    println!("B: {session_b}");
+    println!("{:<32} {:>10} {:>10} {:>10}", "process", "A min", "B min", "delta");
+    for process in processes {
+        let a = minutes(&usage_a, process);
+        let b = minutes(&usage_b, process);
+        println!("{:<32} {:>10.1} {:>10.1} {:>+10.1}", process, a, b, b - a);
+    }
+    let total_a = usage_a.values().sum::<i64>() as f64 / 60_000.0;
+    let total_b = usage_b.values().sum::<i64>() as f64 / 60_000.0;
+    println!("{:<32} {:>10.1} {:>10.1} {:>+10.1}", "total", total_a, total_b, total_b - total_a);
+    Ok(())
+}
+
+/// Milliseconds each process held the foreground, from one active_window_changed to the next.
+/// Paused stretches are not counted, and the last window runs until the session's final event.
+fn foreground_time_by_process(conn: &Connection, session_id: &str) -> Result<HashMap<String, i64>> {
+    // A crash leaves no session_stop; the foreground interval ends at the last event written
+    // before it, since ts_mono_ms keeps running across the gap.
+    let mut stmt = conn.prepare(
+        "SELECT ts_mono_ms, event_type, process_name,
+                CASE WHEN event_type = 'session_resume_after_crash' THEN
+                    (SELECT MAX(p.ts_mono_ms) FROM events p
+                     WHERE p.session_id = e.session_id AND p.ts_mono_ms < e.ts_mono_ms)
+                END
+         FROM events e
+         WHERE session_id = ?1 AND event_type IN
+             ('active_window_changed', 'session_pause', 'session_resume', 'session_stop',
+              'session_resume_after_crash', 'session_lock', 'session_unlock',
+              'secure_desktop_enter', 'secure_desktop_exit')
+         ORDER BY ts_mono_ms, id",
+    )?;
+    let rows = stmt.query_map(params![session_id], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, Option<String>>(2)?,
+            row.get::<_, Option<i64>>(3)?,
+        ))
+    })?;
+    let end_ms: Option<i64> = conn.query_row(
+        "SELECT MAX(ts_mono_ms) FROM events WHERE session_id = ?1",
+        params![session_id],
+        |row| row.get(0),
+    )?;
+
+    let mut usage: HashMap<String, i64> = HashMap::new();
+    let mut current: Option<String> = None;
+    let mut since_ms: Option<i64> = None;
+    // Pause, lock and secure desktop can overlap; the clock restarts once none is active.
+    let (mut paused, mut locked, mut secure) = (false, false, false);
+    let mut close = |current: &Option<String>, since_ms: &mut Option<i64>, now_ms: i64| {
+        if let (Some(process), Some(start_ms)) = (current, since_ms.take()) {
+            *usage.entry(process.clone()).or_insert(0) += (now_ms - start_ms).max(0);
+        }
+    };
+    for row in rows {
+        let (ts_mono_ms, event_type, process_name, last_before_crash_ms) = row?;
+        match event_type.as_str() {
+            "active_window_changed" => {
+                close(&current, &mut since_ms, ts_mono_ms);
+                current = process_name.as_deref().map(normalize_process_name);
+                if !(paused || locked || secure) {
+                    since_ms = Some(ts_mono_ms);
+                }
+            }
+            "session_stop" => {
+                close(&current, &mut since_ms, ts_mono_ms);
+                (paused, locked, secure) = (false, false, false);
+            }
+            "session_resume_after_crash" => {
+                close(&current, &mut since_ms, last_before_crash_ms.unwrap_or(ts_mono_ms));
+                (paused, locked, secure) = (false, false, false);
+                since_ms = Some(ts_mono_ms);
+            }
+            "session_pause" | "session_lock" | "secure_desktop_enter" => {
+                close(&current, &mut since_ms, ts_mono_ms);
+                match event_type.as_str() {
+                    "session_pause" => paused = true,
+                    "session_lock" => locked = true,
+                    _ => secure = true,
+                }
+            }
+            "session_resume" | "session_unlock" | "secure_desktop_exit" => {
+                match event_type.as_str() {
+                    "session_resume" => paused = false,
+                    "session_unlock" => locked = false,
+                    _ => secure = false,
+                }
+                if !(paused || locked || secure) {
+                    since_ms = Some(ts_mono_ms);
+                }
+            }
+            _ => {}
+        }
+    }
+    if let Some(end_ms) = end_ms {
+        close(&current, &mut since_ms, end_ms);
+    }
+    Ok(usage)
 }
 
 fn parse_start_args(mut args: impl Iterator<Item = String>) -> CliOverrides {
@@ -394,20 +1328,52 @@ fn parse_start_args(mut args: impl Iterator<Item = String>) -> CliOverrides {
                     overrides.obs_video_dir = Some(value);
                 }
             }
+            "--resume-latest" => {
+                overrides.resume_latest = true;
+            }
             _ => {}
         }
     }
     overrides
 }
 
+fn print_effective_config(overrides: CliOverrides) -> Result<()> {
+    let base_dir = ensure_app_dir()?;
+    let config = load_config(&base_dir, &overrides)?;
+    let payload = serde_json::to_string_pretty(&config).context("Failed to serialize config")?;
+    println!("{payload}");
+    Ok(())
+}
+
 fn run_recorder(overrides: CliOverrides) -> Result<()> {
     let base_dir = ensure_app_dir()?;
     let config = load_config(&base_dir, &overrides)?;
     let lock_path = base_dir.join(LOCK_FILE);
     if lock_path.exists() {
-        println!("Recorder already running (lock file present).");
-        log_line("Recorder already running (lock file present).");
-        return Ok(());
+        let stale = read_lock_info(&lock_path)
+            .and_then(|info| info.pid)
+            .is_some_and(|pid| !is_pid_running(pid));
+        if !(overrides.resume_latest && stale) {
+            println!("Recorder already running (lock file present).");
+            log_line("Recorder already running (lock file present).");
+            return Ok(());
+        }
+        let _ = fs::remove_file(&lock_path);
+        log_line("Stale lock cleared for --resume-latest.");
+    }
+    if config.min_free_mb > 0 {
+        if let Some(free_mb) = free_disk_mb(&base_dir) {
+            if free_mb < config.min_free_mb {
+                log_line(&format!(
+                    "Refusing to start: {free_mb} MB free, below min_free_mb={}.",
+                    config.min_free_mb
+                ));
+                anyhow::bail!(
+                    "Only {free_mb} MB free on the data volume (min_free_mb is {})",
+                    config.min_free_mb
+                );
+            }
+        }
     }
 
     let _com_guard = ComGuard::new(config.safe_text_only);
@@ -418,15 +1384,34 @@ fn run_recorder(overrides: CliOverrides) -> Result<()> {
         fs::create_dir_all(&icons_dir).context("Failed to create icons dir")?;
     }
 
-    let session_id = Uuid::new_v4().to_string();
-    let start_wall_ms = now_wall_ms();
-    let start_wall_iso = DateTime::<Local>::from(SystemTime::now()).to_rfc3339();
-    let session = SessionInfo {
-        session_id: session_id.clone(),
-        start_wall_ms,
-        start_wall_iso,
-        obs_video_path: config.obs_video_path.clone(),
+    let resumed = if overrides.resume_latest {
+        let resumed = find_crashed_session(&base_dir.join(DB_NAME));
+        if resumed.is_none() {
+            log_line("--resume-latest: latest session ended cleanly; starting a new one.");
+        }
+        resumed
+    } else {
+        None
+    };
+    let session = match resumed.as_ref() {
+        Some(crashed) => SessionInfo {
+            obs_video_path: crashed
+                .session
+                .obs_video_path
+                .clone()
+                .or_else(|| config.obs_video_path.clone()),
+            ..crashed.session.clone()
+        },
+        None => SessionInfo {
+            session_id: Uuid::new_v4().to_string(),
+            start_wall_ms: now_wall_ms(),
+            start_wall_iso: DateTime::<Local>::from(SystemTime::now()).to_rfc3339(),
+            obs_video_path: config.obs_video_path.clone(),
+            schema_version: SCHEMA_VERSION,
+        },
     };
+    let session_id = session.session_id.clone();
+    let start_wall_ms = session.start_wall_ms;
 
     write_lock(&lock_path, &session)?;
     log_line(&format!(
@@ -435,46 +1420,112 @@ fn run_recorder(overrides: CliOverrides) -> Result<()> {
     ));
 
     let shutdown = Arc::new(AtomicBool::new(false));
-    let (tx, rx) = bounded::<EventRecord>(20_000);
+    let (tx, rx) = bounded::<QueuedEvent>(EVENT_QUEUE_CAPACITY);
+    let (selection_tx, selection_rx) = if config.capture_selection_on_copy {
+        let (selection_tx, selection_rx) = bounded::<()>(4);
+        (Some(selection_tx), Some(selection_rx))
+    } else {
+        (None, None)
+    };
+    let queue_near_full = Arc::new(AtomicBool::new(false));
     let start_instant = Instant::now();
+    // A resumed session keeps counting from its original start so ts_mono_ms stays ordered
+    // after the events written before the crash.
+    let mono_offset_ms = if resumed.is_some() {
+        (now_wall_ms() - start_wall_ms).max(0)
+    } else {
+        0
+    };
 
     let state = Arc::new(RecorderState {
         session_id: session_id.clone(),
         sender: tx.clone(),
+        dropped_events: AtomicU64::new(0),
+        shed_events: AtomicU64::new(0),
+        queue_near_full: queue_near_full.clone(),
         start_instant,
+        mono_offset_ms,
         mouse_move_interval_ms: (1000 / config.mouse_hz.max(1)) as i64,
         last_mouse_move_ms: AtomicI64::new(-1),
+        last_mouse_move_pos: Mutex::new(None),
+        mouse_move_min_distance_px: config.mouse_move_min_distance_px as i64,
+        mouse_move_gap_ms: config.mouse_move_gap_ms as i64,
+        mouse_move_buttons: config.mouse_move_buttons,
+        mouse_buttons_down: AtomicU32::new(0),
         paused: AtomicBool::new(false),
         capture_raw_keys: config.capture_raw_keys,
         raw_keys_mode: parse_raw_keys_mode(&config.raw_keys_mode),
+        raw_keys_exclude_vks: config.raw_keys_exclude_vks.iter().copied().collect(),
         suppress_raw_keys_on_shortcut: config.suppress_raw_keys_on_shortcut,
+        suppress_key_repeats: config.suppress_key_repeats,
+        capture_keystroke_timing: config.capture_keystroke_timing,
         exclude_injected_keys: config.exclude_injected_keys,
         emit_mouse_move: AtomicBool::new(config.emit_mouse_move),
         emit_mouse_click: AtomicBool::new(config.emit_mouse_click),
         emit_mouse_scroll: AtomicBool::new(config.emit_mouse_scroll),
         mouse_click_mode: parse_mouse_click_mode(&config.mouse_click_mode),
+        mouse_click_debounce_ms: config.mouse_click_debounce_ms as i64,
+        last_button_down_ms: [AtomicI64::new(i64::MIN), AtomicI64::new(i64::MIN), AtomicI64::new(i64::MIN)],
+        bounced_button_up: [AtomicBool::new(false), AtomicBool::new(false), AtomicBool::new(false)],
+        mouse_coord_space: parse_mouse_coord_space(&config.mouse_coord_space),
         pressed_keys: Mutex::new(HashSet::new()),
+        shortcut_guard_ms: config.shortcut_guard_ms as i64,
+        last_chord_ms: AtomicI64::new(i64::MIN),
+        chord_modifiers_held: AtomicBool::new(false),
         safe_text_only: config.safe_text_only,
         allowlist_processes: config.allowlist_processes.clone(),
         blocklist_processes: config.blocklist_processes.clone(),
+        clipboard_blocklist_titles: config.clipboard_blocklist_titles.clone(),
+        clipboard_image_max_dim: config.clipboard_image_max_dim,
         text_flush_ms: config.text_flush_ms as i64,
         max_text_len: config.max_text_len,
         text_buffer: Mutex::new(TextBuffer {
             text: String::new(),
             last_ts_ms: 0,
+            typed_chars: 0,
+            backspaces: 0,
+            key_intervals_ms: Vec::new(),
+            pending_high_surrogate: None,
         }),
         text_snapshot_on_idle: config.text_snapshot_on_idle,
+        text_snapshot_diff: config.text_snapshot_diff,
+        store_normalized_text: config.store_normalized_text,
+        last_final_text: Mutex::new(HashMap::new()),
         clipboard_dedupe_window_ms: config.clipboard_dedupe_window_ms as i64,
-        last_clipboard_hash: Mutex::new(None),
+        last_clipboard_hash: Mutex::new(VecDeque::new()),
+        clipboard_hash_history: config.clipboard_hash_history.max(1),
+        hash_algorithm: parse_hash_algorithm(&config.clipboard_hash_algorithm),
+        capture_unknown_clipboard: config.capture_unknown_clipboard,
+        clipboard_text_dedupe_ms: config.clipboard_text_dedupe_ms as i64,
+        clipboard_on_copy_only: config.clipboard_on_copy_only,
+        clipboard_copy_window_ms: config.clipboard_copy_window_ms as i64,
+        clipboard_expected_until_ms: AtomicI64::new(i64::MIN),
+        selection_requests: selection_tx,
+        last_clipboard_text_hash: Mutex::new(None),
         app_icon_cache: Mutex::new(HashMap::new()),
         icons_dir: icons_dir.clone(),
+        icon_recapture: config.icon_recapture,
+        icon_size: parse_icon_size(&config.icon_size),
         window_rect_debounce_ms: config.window_rect_debounce_ms as i64,
+        min_window_area_px: config.min_window_area_px as i64,
         window_tracker: Mutex::new(WindowTracker {
             last_hwnd: HWND(0),
             last_rect: None,
+            last_title: String::new(),
             pending_rect: None,
         }),
+        capture_process_info: config.capture_process_info,
+        window_title_events: config.window_title_events,
+        seen_processes: Mutex::new(HashSet::new()),
         scroll_buffer: Mutex::new(None),
+        scroll_coalesce_ms: config.scroll_coalesce_ms as i64,
+        exclude_event_types: config.exclude_event_types.iter().cloned().collect(),
+        max_payload_bytes: config.max_payload_bytes,
+        safe_text_fallback: parse_safe_text_fallback(&config.safe_text_fallback),
+        uia_unavailable_reported: AtomicBool::new(false),
+        text_flush_reasons: config.text_flush_reasons.iter().map(|reason| reason.trim().to_string()).collect(),
+        preserialize_events: config.preserialize_events,
+        live_stream_enabled: config.live_stream_addr.is_some(),
         last_virtual_desktop_id: Mutex::new(None),
     });
     let _ = STATE.set(state.clone());
@@ -483,7 +1534,30 @@ fn run_recorder(overrides: CliOverrides) -> Result<()> {
     let db_path_writer = db_path.clone();
     let writer_shutdown = shutdown.clone();
     let session_for_writer = session.clone();
-    let writer_handle = thread::spawn(move || run_writer(rx, &db_path_writer, session_for_writer, writer_shutdown));
+    let (live_tx, live_handle) = match config.live_stream_addr.as_deref() {
+        Some(addr) => match spawn_live_stream(addr, shutdown.clone()) {
+            Ok((live_tx, handle)) => (Some(live_tx), Some(handle)),
+            Err(err) => {
+                log_line(&format!("Live stream disabled: {err:#}"));
+                (None, None)
+            }
+        },
+        None => (None, None),
+    };
+    let writer_done = Arc::new(AtomicBool::new(false));
+    let writer_handle = thread::spawn({
+        let writer_done = writer_done.clone();
+        move || {
+            run_writer(rx, &db_path_writer, session_for_writer, writer_shutdown, queue_near_full, live_tx);
+            writer_done.store(true, Ordering::SeqCst);
+        }
+    });
+    let _ = END_SESSION.set(EndSessionContext {
+        shutdown: shutdown.clone(),
+        writer_done,
+        stop_sent: AtomicBool::new(false),
+        lock_path: lock_path.clone(),
+    });
 
     ctrlc::set_handler({
         let shutdown = shutdown.clone();
@@ -494,25 +1568,109 @@ fn run_recorder(overrides: CliOverrides) -> Result<()> {
     })
     .context("Failed to set Ctrl+C handler")?;
 
-    send_session_event(&state, "session_start", json!({ "note": "manual_start" }));
+    match resumed.as_ref() {
+        Some(crashed) => send_session_event(
+            &state,
+            "session_resume_after_crash",
+            json!({
+                "note": "resume_latest",
+                "schema_version": session.schema_version,
+                "last_event_wall_ms": crashed.last_event_wall_ms,
+                "gap_ms": crashed.last_event_wall_ms.map(|last| now_wall_ms() - last),
+            }),
+        ),
+        None => send_session_event(
+            &state,
+            "session_start",
+            json!({ "note": "manual_start", "schema_version": session.schema_version }),
+        ),
+    }
+    // The hooks run on this thread, so this is the UIA instance should_capture_text will use.
+    if config.safe_text_only && get_uia().is_none() {
+        report_uia_unavailable(&state);
+    }
 
     let stop_signal_path = base_dir.join(STOP_FILE);
     let stop_handle = spawn_stop_watcher(state.clone(), stop_signal_path, shutdown.clone(), main_thread_id);
     let pause_signal_path = base_dir.join(PAUSE_FILE);
     let pause_handle = spawn_pause_watcher(state.clone(), pause_signal_path, shutdown.clone());
-    let scroll_flush_handle = spawn_scroll_flush(state.clone(), shutdown.clone());
-    let reload_signal_path = base_dir.join(RELOAD_CONFIG_FILE);
+    // focus_only installs no input hooks and starts none of the clipboard, scroll or snapshot watchers,
+    // so only window focus and app usage reach the database.
+    let focus_only = parse_capture_profile(&config.capture_profile) == CaptureProfile::FocusOnly;
+    if focus_only {
+        log_line("Capture profile focus_only: input, clipboard and snapshot capture disabled.");
+    }
+    let scroll_flush_handle = if focus_only {
+        None
+    } else {
+        Some(spawn_scroll_flush(state.clone(), shutdown.clone()))
+    };
+    let clock_handle = if config.clock_drift_threshold_ms > 0 {
+        Some(spawn_clock_watcher(
+            state.clone(),
+            shutdown.clone(),
+            config.clock_drift_threshold_ms as i64,
+        ))
+    } else {
+        None
+    };
+    let heartbeat_handle = if config.heartbeat_ms > 0 {
+        Some(spawn_heartbeat_loop(state.clone(), shutdown.clone(), config.heartbeat_ms))
+    } else {
+        None
+    };
+    let disk_handle = if config.min_free_mb > 0 {
+        Some(spawn_disk_space_watcher(
+            state.clone(),
+            shutdown.clone(),
+            base_dir.clone(),
+            config.min_free_mb,
+            config.low_disk_auto_pause,
+        ))
+    } else {
+        None
+    };
+    let secure_desktop_handle = if config.detect_secure_desktop {
+        Some(spawn_secure_desktop_watcher(state.clone(), shutdown.clone()))
+    } else {
+        None
+    };
+    let reload_signal_path = base_dir.join(RELOAD_CONFIG_FILE);
     let reload_handle = spawn_config_reload_watcher(
         state.clone(),
         reload_signal_path,
         base_dir.clone(),
         shutdown.clone(),
     );
-    let snapshot_handle = if config.emit_snapshots {
+    let control_pipe_handle = if config.control_pipe {
+        Some(spawn_control_pipe_server(
+            state.clone(),
+            base_dir.clone(),
+            shutdown.clone(),
+            main_thread_id,
+        ))
+    } else {
+        None
+    };
+    let snapshot_handle = if config.emit_snapshots && !focus_only {
         Some(spawn_snapshot_loop(
             state.clone(),
             shutdown.clone(),
-            config.snapshot_hz,
+            SnapshotCadence {
+                active_hz: config.snapshot_active_hz.unwrap_or(config.snapshot_hz as f64),
+                idle_hz: config.snapshot_idle_hz,
+                idle_after_ms: config.snapshot_idle_after_ms,
+            },
+        ))
+    } else {
+        None
+    };
+    let screenshot_handle = if config.self_screenshot_hz > 0.0 && !focus_only {
+        Some(spawn_screenshot_loop(
+            state.clone(),
+            shutdown.clone(),
+            config.self_screenshot_hz,
+            base_dir.clone(),
         ))
     } else {
         None
@@ -526,7 +1684,7 @@ fn run_recorder(overrides: CliOverrides) -> Result<()> {
     } else {
         None
     };
-    let clipboard_handle = if config.capture_clipboard {
+    let clipboard_handle = if config.capture_clipboard && !focus_only {
         Some(spawn_clipboard_loop(
             state.clone(),
             shutdown.clone(),
@@ -537,14 +1695,40 @@ fn run_recorder(overrides: CliOverrides) -> Result<()> {
     } else {
         None
     };
+    let selection_handle = match selection_rx {
+        Some(selection_rx) if !focus_only => {
+            Some(spawn_selection_worker(state.clone(), shutdown.clone(), selection_rx))
+        }
+        _ => None,
+    };
     let rect_flush_handle = if config.window_rect_debounce_ms > 0 {
         Some(spawn_window_rect_flush_loop(state.clone(), shutdown.clone()))
     } else {
         None
     };
 
-    let (mouse_hook, keyboard_hook) = install_hooks()?;
-    let (foreground_hook, location_hook) = install_window_event_hooks()?;
+    HOOK_THREAD.with(|flag| flag.set(true));
+    let input_hooks = if focus_only { None } else { Some(install_hooks()?) };
+    let session_window = match create_session_window() {
+        Ok(hwnd) => Some(hwnd),
+        Err(err) => {
+            log_line(&format!("Session window unavailable; logoff will not flush: {err:#}"));
+            None
+        }
+    };
+    let session_notifications = match session_window {
+        Some(hwnd) if config.detect_session_changes => {
+            match unsafe { WTSRegisterSessionNotification(hwnd, NOTIFY_FOR_THIS_SESSION) } {
+                Ok(()) => true,
+                Err(err) => {
+                    log_line(&format!("Session change notifications unavailable: {err}"));
+                    false
+                }
+            }
+        }
+        _ => false,
+    };
+    let (foreground_hook, location_hook, name_hook) = install_window_event_hooks(config.window_title_events)?;
 
     unsafe {
         let mut msg = MSG::default();
@@ -557,33 +1741,83 @@ fn run_recorder(overrides: CliOverrides) -> Result<()> {
         }
     }
 
-    flush_text_buffer(&state, "session_end");
-    shutdown.store(true, Ordering::SeqCst);
-    send_session_event(&state, "session_stop", json!({ "note": "manual_stop" }));
+    let stop_sent = END_SESSION
+        .get()
+        .is_some_and(|context| context.stop_sent.load(Ordering::SeqCst));
+    if !stop_sent {
+        if !shutdown.load(Ordering::SeqCst) {
+            log_line("Message loop ended without a stop request; flushing session.");
+        }
+        flush_text_buffer(&state, "session_end");
+        shutdown.store(true, Ordering::SeqCst);
+        send_session_event(&state, "session_stop", json!({ "note": "manual_stop" }));
+    }
     unsafe {
-        let _ = UnhookWindowsHookEx(mouse_hook);
-        let _ = UnhookWindowsHookEx(keyboard_hook);
+        if let Some(hwnd) = session_window {
+            if session_notifications {
+                let _ = WTSUnRegisterSessionNotification(hwnd);
+            }
+            let _ = DestroyWindow(hwnd);
+        }
+        if let Some((mouse_hook, keyboard_hook)) = input_hooks {
+            let _ = UnhookWindowsHookEx(mouse_hook);
+            let _ = UnhookWindowsHookEx(keyboard_hook);
+        }
         let _ = UnhookWinEvent(foreground_hook);
         let _ = UnhookWinEvent(location_hook);
+        if let Some(name_hook) = name_hook {
+            let _ = UnhookWinEvent(name_hook);
+        }
     }
 
     stop_handle.join().ok();
     pause_handle.join().ok();
-    scroll_flush_handle.join().ok();
+    if let Some(handle) = scroll_flush_handle {
+        handle.join().ok();
+    }
     reload_handle.join().ok();
+    if let Some(handle) = secure_desktop_handle {
+        handle.join().ok();
+    }
+    if let Some(handle) = heartbeat_handle {
+        handle.join().ok();
+    }
+    if let Some(handle) = clock_handle {
+        handle.join().ok();
+    }
+    if let Some(handle) = disk_handle {
+        handle.join().ok();
+    }
+    if let Some(handle) = control_pipe_handle {
+        wake_control_pipe(&handle);
+        if handle.is_finished() {
+            handle.join().ok();
+        } else {
+            log_line("Control pipe thread did not exit; leaving it detached.");
+        }
+    }
     if let Some(handle) = snapshot_handle {
         handle.join().ok();
     }
+    if let Some(handle) = screenshot_handle {
+        handle.join().ok();
+    }
     if let Some(handle) = window_poll_handle {
         handle.join().ok();
     }
     if let Some(handle) = rect_flush_handle {
         handle.join().ok();
     }
+    if let Some(handle) = selection_handle {
+        handle.join().ok();
+    }
     if let Some(handle) = clipboard_handle {
         handle.join().ok();
     }
     writer_handle.join().ok();
+    if let Some(handle) = live_handle {
+        handle.join().ok();
+    }
     if session.obs_video_path.is_none() {
         if let Some(path) = resolve_obs_video_path(&config, &session) {
             if let Err(err) = update_session_obs_path(&db_path, &session.session_id, &path) {
@@ -596,6 +1830,39 @@ fn run_recorder(overrides: CliOverrides) -> Result<()> {
     Ok(())
 }
 
+struct CrashedSession {
+    session: SessionInfo,
+    last_event_wall_ms: Option<i64>,
+}
+
+/// The most recent session when it never recorded a `session_stop`, meaning the recorder died
+/// without shutting down.
+fn find_crashed_session(db_path: &Path) -> Option<CrashedSession> {
+    let conn = Connection::open(db_path).ok()?;
+    conn.query_row(
+        "SELECT s.session_id, s.start_wall_ms, s.start_wall_iso, s.obs_video_path, s.schema_version,
+                (SELECT MAX(ts_wall_ms) FROM events e WHERE e.session_id = s.session_id)
+         FROM sessions s
+         WHERE NOT EXISTS (
+             SELECT 1 FROM events e WHERE e.session_id = s.session_id AND e.event_type = 'session_stop'
+         ) AND s.start_wall_ms = (SELECT MAX(start_wall_ms) FROM sessions)",
+        [],
+        |row| {
+            Ok(CrashedSession {
+                session: SessionInfo {
+                    session_id: row.get(0)?,
+                    start_wall_ms: row.get(1)?,
+                    start_wall_iso: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+                    obs_video_path: row.get(3)?,
+                    schema_version: row.get::<_, Option<i64>>(4)?.unwrap_or(SCHEMA_VERSION),
+                },
+                last_event_wall_ms: row.get(5)?,
+            })
+        },
+    )
+    .ok()
+}
+
 fn stop_recorder() -> Result<()> {
     let base_dir = ensure_app_dir()?;
     let lock_path = base_dir.join(LOCK_FILE);
@@ -604,6 +1871,11 @@ fn stop_recorder() -> Result<()> {
         log_line("Stop requested but no active session found.");
         return Ok(());
     }
+    if send_control_command("stop") {
+        println!("Stop sent via control pipe.");
+        log_line("Stop sent via control pipe.");
+        return Ok(());
+    }
     let stop_path = base_dir.join(STOP_FILE);
     fs::write(stop_path, b"stop")?;
     let pause_path = base_dir.join(PAUSE_FILE);
@@ -634,14 +1906,12 @@ fn spawn_pause_watcher(
         while !shutdown.load(Ordering::SeqCst) {
             let paused = pause_path.exists();
             if paused != last_paused {
-                state.paused.store(paused, Ordering::SeqCst);
-                if paused {
-                    flush_text_buffer(&state, "pause");
-                    send_session_event(&state, "session_pause", json!({ "note": "pause_signal" }));
-                    log_line("Pause signal observed; session paused.");
-                } else {
-                    send_session_event(&state, "session_resume", json!({ "note": "pause_signal" }));
-                    log_line("Pause cleared; session resumed.");
+                if set_paused(&state, paused, "pause_signal") {
+                    if paused {
+                        log_line("Pause signal observed; session paused.");
+                    } else {
+                        log_line("Pause cleared; session resumed.");
+                    }
                 }
                 last_paused = paused;
             }
@@ -650,6 +1920,31 @@ fn spawn_pause_watcher(
     })
 }
 
+fn set_paused(state: &RecorderState, paused: bool, note: &str) -> bool {
+    if state.paused.swap(paused, Ordering::SeqCst) == paused {
+        return false;
+    }
+    if paused {
+        flush_text_buffer(state, "pause");
+        send_session_event(state, "session_pause", json!({ "note": note }));
+    } else {
+        send_session_event(state, "session_resume", json!({ "note": note }));
+    }
+    true
+}
+
+fn request_stop(state: &RecorderState, shutdown: &AtomicBool, main_thread_id: u32) {
+    flush_text_buffer(state, "stop_signal");
+    signal_shutdown(shutdown, main_thread_id);
+}
+
+fn reload_config(state: &RecorderState, base_dir: &Path) {
+    if let Ok(config) = load_or_create_config(&base_dir.join(CONFIG_FILE)) {
+        let config = normalize_config(config);
+        apply_capture_flags(state, &config);
+    }
+}
+
 fn spawn_config_reload_watcher(
     state: Arc<RecorderState>,
     reload_path: PathBuf,
@@ -662,10 +1957,7 @@ fn spawn_config_reload_watcher(
             if reload_path.exists() {
                 let _ = fs::remove_file(&reload_path);
                 log_line("Config reload signal observed.");
-                if let Ok(config) = load_or_create_config(&base_dir.join(CONFIG_FILE)) {
-                    let config = normalize_config(config);
-                    apply_capture_flags(&state, &config);
-                }
+                reload_config(&state, &base_dir);
             }
             thread::sleep(interval);
         }
@@ -677,12 +1969,13 @@ fn spawn_scroll_flush(
     shutdown: Arc<AtomicBool>,
 ) -> thread::JoinHandle<()> {
     thread::spawn(move || {
-        let interval = Duration::from_millis(120);
+        // Poll at least as often as the coalescing window so short windows still close on time.
+        let interval = Duration::from_millis((state.scroll_coalesce_ms as u64).clamp(10, 120));
         while !shutdown.load(Ordering::SeqCst) {
             {
                 let mut buffer = state.scroll_buffer.lock().unwrap();
                 if let Some(existing) = buffer.as_ref() {
-                    if now_mono_ms(&state) - existing.last_ts_ms > 200 {
+                    if now_mono_ms(&state) - existing.last_ts_ms > state.scroll_coalesce_ms {
                         flush_scroll_buffer(&state, &mut buffer);
                     }
                 }
@@ -692,7 +1985,313 @@ fn spawn_scroll_flush(
     })
 }
 
+fn spawn_heartbeat_loop(
+    state: Arc<RecorderState>,
+    shutdown: Arc<AtomicBool>,
+    heartbeat_ms: u64,
+) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        let interval = Duration::from_millis(heartbeat_ms);
+        let tick = Duration::from_millis(250).min(interval);
+        let mut last_beat = Instant::now();
+        while !shutdown.load(Ordering::SeqCst) {
+            if last_beat.elapsed() >= interval {
+                last_beat = Instant::now();
+                send_session_event(
+                    &state,
+                    "heartbeat",
+                    json!({
+                        "queue_len": state.sender.len(),
+                        "dropped_events": state.dropped_events.load(Ordering::Relaxed),
+                        "shed_events": state.shed_events.load(Ordering::Relaxed),
+                        "paused": state.paused.load(Ordering::SeqCst),
+                    }),
+                );
+            }
+            thread::sleep(tick);
+        }
+    })
+}
+
+/// Tracks the wall-minus-mono offset so consumers aligning video by `ts_wall_ms` can correct for
+/// NTP steps and manual clock changes. A tick that took far longer than scheduled means the
+/// machine was suspended, which is reported as `system_resume`.
+fn spawn_clock_watcher(
+    state: Arc<RecorderState>,
+    shutdown: Arc<AtomicBool>,
+    threshold_ms: i64,
+) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        let interval = Duration::from_millis(1000);
+        let mut offset_ms = now_wall_ms() - now_mono_ms(&state);
+        let mut last_wall_ms = now_wall_ms();
+        while !shutdown.load(Ordering::SeqCst) {
+            thread::sleep(interval);
+            let wall_ms = now_wall_ms();
+            let mono_ms = now_mono_ms(&state);
+            let gap_ms = wall_ms - last_wall_ms;
+            last_wall_ms = wall_ms;
+            let new_offset_ms = wall_ms - mono_ms;
+            let delta_ms = new_offset_ms - offset_ms;
+            if gap_ms - interval.as_millis() as i64 > threshold_ms.max(30_000) {
+                send_session_event(&state, "system_resume", json!({ "gap_ms": gap_ms }));
+            }
+            if delta_ms.abs() >= threshold_ms {
+                offset_ms = new_offset_ms;
+                send_session_event(
+                    &state,
+                    "clock_adjustment",
+                    json!({
+                        "delta_ms": delta_ms,
+                        "wall_minus_mono_ms": new_offset_ms,
+                    }),
+                );
+            }
+        }
+    })
+}
+
+/// Emits `low_disk` once each time free space on the data volume falls below `min_free_mb`,
+/// optionally pausing so the database is not left half-written when the disk fills.
+fn spawn_disk_space_watcher(
+    state: Arc<RecorderState>,
+    shutdown: Arc<AtomicBool>,
+    base_dir: PathBuf,
+    min_free_mb: u64,
+    auto_pause: bool,
+) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        let interval = Duration::from_millis(DISK_CHECK_INTERVAL_MS);
+        let tick = Duration::from_millis(250);
+        let mut last_check = Instant::now();
+        let mut low = false;
+        while !shutdown.load(Ordering::SeqCst) {
+            thread::sleep(tick);
+            if last_check.elapsed() < interval {
+                continue;
+            }
+            last_check = Instant::now();
+            let Some(free_mb) = free_disk_mb(&base_dir) else {
+                continue;
+            };
+            if free_mb >= min_free_mb {
+                if low {
+                    log_line(&format!("Free disk space recovered: {free_mb} MB."));
+                }
+                low = false;
+                continue;
+            }
+            if low {
+                continue;
+            }
+            low = true;
+            log_line(&format!("Low disk space: {free_mb} MB free (min_free_mb={min_free_mb})."));
+            send_session_event(
+                &state,
+                "low_disk",
+                json!({
+                    "free_mb": free_mb,
+                    "min_free_mb": min_free_mb,
+                    "auto_pause": auto_pause,
+                }),
+            );
+            if auto_pause && set_paused(&state, true, "low_disk") {
+                log_line("Session paused for low disk space.");
+            }
+        }
+    })
+}
+
+fn free_disk_mb(path: &Path) -> Option<u64> {
+    let mut wide: Vec<u16> = path.to_string_lossy().encode_utf16().collect();
+    wide.push(0);
+    let mut free_bytes: u64 = 0;
+    unsafe { GetDiskFreeSpaceExW(PCWSTR(wide.as_ptr()), Some(&mut free_bytes), None, None) }.ok()?;
+    Some(free_bytes / (1024 * 1024))
+}
+
+fn spawn_secure_desktop_watcher(
+    state: Arc<RecorderState>,
+    shutdown: Arc<AtomicBool>,
+) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        let interval = Duration::from_millis(500);
+        let mut entered_ms: Option<i64> = None;
+        while !shutdown.load(Ordering::SeqCst) {
+            let desktop = input_desktop_name();
+            let is_secure = desktop.as_deref().is_none_or(|name| !name.eq_ignore_ascii_case("Default"));
+            match (is_secure, entered_ms) {
+                (true, None) => {
+                    entered_ms = Some(now_mono_ms(&state));
+                    if !state.paused.load(Ordering::SeqCst) {
+                        flush_text_buffer(&state, "secure_desktop");
+                        send_session_event(&state, "secure_desktop_enter", json!({ "desktop": desktop }));
+                    }
+                }
+                (false, Some(since_ms)) => {
+                    entered_ms = None;
+                    if !state.paused.load(Ordering::SeqCst) {
+                        send_session_event(
+                            &state,
+                            "secure_desktop_exit",
+                            json!({ "duration_ms": now_mono_ms(&state) - since_ms }),
+                        );
+                    }
+                }
+                _ => {}
+            }
+            thread::sleep(interval);
+        }
+    })
+}
+
+/// Name of the desktop currently receiving input; `None` when it can't be opened, which is what
+/// happens while the UAC prompt or lock screen owns the secure (Winlogon) desktop.
+fn input_desktop_name() -> Option<String> {
+    unsafe {
+        let desktop = OpenInputDesktop(DESKTOP_CONTROL_FLAGS(0), false, DESKTOP_READOBJECTS).ok()?;
+        let mut buffer = [0u16; 256];
+        let mut needed = 0u32;
+        let result = GetUserObjectInformationW(
+            HANDLE(desktop.0),
+            UOI_NAME,
+            Some(buffer.as_mut_ptr() as *mut c_void),
+            (buffer.len() * 2) as u32,
+            Some(&mut needed),
+        );
+        let _ = CloseDesktop(desktop);
+        result.ok()?;
+        let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+        Some(String::from_utf16_lossy(&buffer[..len]))
+    }
+}
+
+fn spawn_control_pipe_server(
+    state: Arc<RecorderState>,
+    base_dir: PathBuf,
+    shutdown: Arc<AtomicBool>,
+    main_thread_id: u32,
+) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        let mut name: Vec<u16> = CONTROL_PIPE_NAME.encode_utf16().collect();
+        name.push(0);
+        log_line(&format!("Control pipe listening on {CONTROL_PIPE_NAME}"));
+        while !shutdown.load(Ordering::SeqCst) {
+            let pipe = unsafe {
+                CreateNamedPipeW(
+                    PCWSTR(name.as_ptr()),
+                    PIPE_ACCESS_DUPLEX,
+                    PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT,
+                    1,
+                    512,
+                    512,
+                    0,
+                    None,
+                )
+            };
+            if pipe.is_invalid() {
+                log_line("Failed to create control pipe; falling back to signal files only.");
+                break;
+            }
+            let connected = match unsafe { ConnectNamedPipe(pipe, None) } {
+                Ok(()) => true,
+                Err(err) => err.code() == ERROR_PIPE_CONNECTED.to_hresult(),
+            };
+            if connected {
+                let mut pending = String::new();
+                let mut buffer = [0u8; 512];
+                while !shutdown.load(Ordering::SeqCst) {
+                    let mut read: u32 = 0;
+                    if unsafe { ReadFile(pipe, Some(&mut buffer), Some(&mut read), None) }.is_err() || read == 0 {
+                        break;
+                    }
+                    pending.push_str(&String::from_utf8_lossy(&buffer[..read as usize]));
+                    while let Some(end) = pending.find('\n') {
+                        let line: String = pending.drain(..=end).collect();
+                        let reply = handle_control_command(&state, &base_dir, &shutdown, main_thread_id, &line);
+                        let reply = format!("{reply}\n");
+                        let _ = unsafe { WriteFile(pipe, Some(reply.as_bytes()), None, None) };
+                    }
+                }
+            }
+            unsafe {
+                let _ = DisconnectNamedPipe(pipe);
+                let _ = CloseHandle(pipe);
+            }
+        }
+    })
+}
+
+fn handle_control_command(
+    state: &RecorderState,
+    base_dir: &Path,
+    shutdown: &AtomicBool,
+    main_thread_id: u32,
+    command: &str,
+) -> &'static str {
+    match command.trim().to_ascii_lowercase().as_str() {
+        "stop" => {
+            let _ = fs::remove_file(base_dir.join(PAUSE_FILE));
+            log_line("Stop command received on control pipe; shutting down.");
+            request_stop(state, shutdown, main_thread_id);
+            "ok"
+        }
+        "pause" => {
+            // Mirror the pause file so `status` and the tray keep reporting correctly.
+            let _ = fs::write(base_dir.join(PAUSE_FILE), b"pause");
+            if set_paused(state, true, "control_pipe") {
+                log_line("Pause command received on control pipe; session paused.");
+            }
+            "ok"
+        }
+        "resume" => {
+            let _ = fs::remove_file(base_dir.join(PAUSE_FILE));
+            if set_paused(state, false, "control_pipe") {
+                log_line("Resume command received on control pipe; session resumed.");
+            }
+            "ok"
+        }
+        "reload" => {
+            log_line("Config reload command received on control pipe.");
+            reload_config(state, base_dir);
+            "ok"
+        }
+        _ => "unknown",
+    }
+}
+
+fn wake_control_pipe(handle: &thread::JoinHandle<()>) {
+    // ConnectNamedPipe blocks until a client shows up, so connect once to let the thread see shutdown.
+    for _ in 0..20 {
+        if handle.is_finished() {
+            return;
+        }
+        let _ = OpenOptions::new().read(true).write(true).open(CONTROL_PIPE_NAME);
+        thread::sleep(Duration::from_millis(50));
+    }
+}
+
+fn send_control_command(command: &str) -> bool {
+    let mut pipe = match OpenOptions::new().read(true).write(true).open(CONTROL_PIPE_NAME) {
+        Ok(pipe) => pipe,
+        Err(_) => return false,
+    };
+    if writeln!(pipe, "{command}").is_err() {
+        return false;
+    }
+    let mut reply = String::new();
+    if BufReader::new(pipe).read_line(&mut reply).is_err() {
+        return false;
+    }
+    reply.trim() == "ok"
+}
+
 fn pause_recorder() -> Result<()> {
+    if send_control_command("pause") {
+        println!("Pause sent via control pipe.");
+        log_line("Pause sent via control pipe.");
+        return Ok(());
+    }
     let base_dir = ensure_app_dir()?;
     let pause_path = base_dir.join(PAUSE_FILE);
     fs::write(pause_path, b"pause")?;
@@ -702,6 +2301,11 @@ fn pause_recorder() -> Result<()> {
 }
 
 fn resume_recorder() -> Result<()> {
+    if send_control_command("resume") {
+        println!("Resume sent via control pipe.");
+        log_line("Resume sent via control pipe.");
+        return Ok(());
+    }
     let base_dir = ensure_app_dir()?;
     let pause_path = base_dir.join(PAUSE_FILE);
     if pause_path.exists() {
@@ -716,9 +2320,17 @@ fn toggle_pause() -> Result<()> {
     let base_dir = ensure_app_dir()?;
     let pause_path = base_dir.join(PAUSE_FILE);
     if pause_path.exists() {
+        if send_control_command("resume") {
+            println!("Resume sent via control pipe.");
+            log_line("Resume sent via control pipe.");
+            return Ok(());
+        }
         let _ = fs::remove_file(pause_path);
         println!("Resume signal written.");
         log_line("Resume signal written.");
+    } else if send_control_command("pause") {
+        println!("Pause sent via control pipe.");
+        log_line("Pause sent via control pipe.");
     } else {
         fs::write(pause_path, b"pause")?;
         println!("Pause signal written.");
@@ -727,39 +2339,53 @@ fn toggle_pause() -> Result<()> {
     Ok(())
 }
 
-fn print_status() -> Result<()> {
+fn print_status(json_output: bool) -> Result<()> {
     let base_dir = ensure_app_dir()?;
     let lock_path = base_dir.join(LOCK_FILE);
     let pause_path = base_dir.join(PAUSE_FILE);
-    if !lock_path.exists() {
-        println!("Recorder status: stopped");
-        return Ok(());
-    }
-    if let Some(info) = read_lock_info(&lock_path) {
-        if let Some(pid) = info.pid {
+    let mut info = None;
+    let mut note = None;
+    if lock_path.exists() {
+        info = read_lock_info(&lock_path);
+        if let Some(pid) = info.as_ref().and_then(|info| info.pid) {
             if !is_pid_running(pid) {
                 let _ = fs::remove_file(&lock_path);
-                println!("Recorder status: stopped (stale lock cleared)");
-                return Ok(());
-            }
-        }
-        if pause_path.exists() {
-            println!("Recorder status: paused");
-        } else {
-            println!("Recorder status: running");
-        }
-        if let Some(contents) = info.raw {
-            if !contents.trim().is_empty() {
-                println!("{contents}");
+                info = None;
+                note = Some("stale lock cleared");
             }
         }
+    }
+    let running = lock_path.exists();
+    let paused = running && pause_path.exists();
+    let status = if paused {
+        "paused"
+    } else if running {
+        "running"
     } else {
-        if pause_path.exists() {
-            println!("Recorder status: paused");
-        } else {
-            println!("Recorder status: running");
-        }
-        let contents = fs::read_to_string(lock_path).unwrap_or_default();
+        "stopped"
+    };
+
+    if json_output {
+        let info = info.as_ref();
+        println!(
+            "{}",
+            json!({
+                "status": status,
+                "session_id": info.and_then(|info| info.session_id.clone()),
+                "pid": info.and_then(|info| info.pid),
+                "start_wall_ms": info.and_then(|info| info.start_wall_ms),
+                "start_wall_iso": info.and_then(|info| info.start_wall_iso.clone()),
+                "paused": paused,
+            })
+        );
+        return Ok(());
+    }
+
+    match note {
+        Some(note) => println!("Recorder status: {status} ({note})"),
+        None => println!("Recorder status: {status}"),
+    }
+    if let Some(contents) = info.and_then(|info| info.raw) {
         if !contents.trim().is_empty() {
             println!("{contents}");
         }
@@ -767,9 +2393,124 @@ fn print_status() -> Result<()> {
     Ok(())
 }
 
+struct DoctorCheck {
+    name: &'static str,
+    critical: bool,
+    outcome: std::result::Result<String, String>,
+}
+
+/// Environment self-check; only critical failures make the command exit nonzero.
+fn run_doctor() -> Result<()> {
+    let mut checks = Vec::new();
+    let base_dir = ensure_app_dir();
+    checks.push(DoctorCheck {
+        name: "data dir",
+        critical: true,
+        outcome: match base_dir.as_ref() {
+            Ok(dir) => {
+                let probe = dir.join("doctor.probe");
+                match fs::write(&probe, b"ok") {
+                    Ok(()) => {
+                        let _ = fs::remove_file(&probe);
+                        Ok(dir.display().to_string())
+                    }
+                    Err(err) => Err(format!("{} is not writable: {err}", dir.display())),
+                }
+            }
+            Err(err) => Err(format!("{err:#}")),
+        },
+    });
+    if let Ok(dir) = base_dir.as_ref() {
+        checks.push(DoctorCheck {
+            name: "config",
+            critical: false,
+            outcome: load_or_create_config(&dir.join(CONFIG_FILE))
+                .map(|_| dir.join(CONFIG_FILE).display().to_string())
+                .map_err(|err| format!("{err:#}")),
+        });
+        let db_path = dir.join(DB_NAME);
+        checks.push(DoctorCheck {
+            name: "database",
+            critical: true,
+            outcome: Connection::open(&db_path)
+                .map_err(anyhow::Error::from)
+                .and_then(|conn| init_db(&conn))
+                .map(|_| db_path.display().to_string())
+                .map_err(|err| format!("{err:#}")),
+        });
+        let lock_path = dir.join(LOCK_FILE);
+        let running_pid = read_lock_info(&lock_path)
+            .and_then(|info| info.pid)
+            .filter(|pid| is_pid_running(*pid));
+        checks.push(DoctorCheck {
+            name: "recorder",
+            critical: false,
+            outcome: Ok(match running_pid {
+                Some(pid) => format!("already running (pid {pid})"),
+                None => "not running".to_string(),
+            }),
+        });
+    }
+
+    let com_guard = ComGuard::new(false);
+    checks.push(DoctorCheck {
+        name: "COM",
+        critical: true,
+        outcome: if com_guard.initialized {
+            Ok("apartment initialized".to_string())
+        } else {
+            Err("CoInitializeEx failed".to_string())
+        },
+    });
+    checks.push(DoctorCheck {
+        name: "UI Automation",
+        critical: false,
+        outcome: if com_guard.initialized && get_uia().is_some() {
+            Ok("available".to_string())
+        } else {
+            Err("CUIAutomation unavailable; safe text capture will drop all text".to_string())
+        },
+    });
+    for tool in ["ffmpeg", "python"] {
+        checks.push(DoctorCheck {
+            name: tool,
+            critical: false,
+            outcome: find_on_path(tool)
+                .map(|path| path.display().to_string())
+                .ok_or_else(|| "not found on PATH (needed by the processing pipeline)".to_string()),
+        });
+    }
+
+    let mut critical_failures = 0;
+    for check in &checks {
+        match &check.outcome {
+            Ok(detail) => println!("[PASS] {}: {}", check.name, detail),
+            Err(detail) if check.critical => {
+                critical_failures += 1;
+                println!("[FAIL] {}: {}", check.name, detail);
+            }
+            Err(detail) => println!("[WARN] {}: {}", check.name, detail),
+        }
+    }
+    if critical_failures > 0 {
+        anyhow::bail!("{critical_failures} critical check(s) failed");
+    }
+    Ok(())
+}
+
+fn find_on_path(tool: &str) -> Option<PathBuf> {
+    let path = env::var_os("PATH")?;
+    env::split_paths(&path)
+        .flat_map(|dir| [dir.join(format!("{tool}.exe")), dir.join(tool)])
+        .find(|candidate| candidate.is_file())
+}
+
+/// Uses `TIMESTONE_DATA_DIR` when set, otherwise `data\timestone` under the current directory.
 fn ensure_app_dir() -> Result<PathBuf> {
-    let cwd = env::current_dir().context("Failed to read current dir")?;
-    let base_dir = cwd.join(APP_DIR);
+    let base_dir = match env::var_os(DATA_DIR_ENV) {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => env::current_dir().context("Failed to read current dir")?.join(APP_DIR),
+    };
     if !base_dir.exists() {
         fs::create_dir_all(&base_dir).context("Failed to create timestone data dir")?;
     }
@@ -786,6 +2527,109 @@ fn write_lock(path: &Path, session: &SessionInfo) -> Result<()> {
     Ok(())
 }
 
+struct EndSessionContext {
+    shutdown: Arc<AtomicBool>,
+    writer_done: Arc<AtomicBool>,
+    stop_sent: AtomicBool,
+    lock_path: PathBuf,
+}
+
+/// Hidden top-level window; message-only windows never see the WM_QUERYENDSESSION broadcast.
+fn create_session_window() -> Result<HWND> {
+    unsafe {
+        let module = GetModuleHandleW(None).context("Failed to get module handle")?;
+        let class_name: Vec<u16> = SESSION_WINDOW_CLASS.encode_utf16().chain(Some(0)).collect();
+        let wc = WNDCLASSW {
+            lpfnWndProc: Some(session_window_proc),
+            hInstance: module.into(),
+            lpszClassName: PCWSTR(class_name.as_ptr()),
+            ..Default::default()
+        };
+        RegisterClassW(&wc);
+        let hwnd = CreateWindowExW(
+            Default::default(),
+            PCWSTR(class_name.as_ptr()),
+            PCWSTR(class_name.as_ptr()),
+            WS_OVERLAPPED,
+            0,
+            0,
+            0,
+            0,
+            HWND(0),
+            HMENU(0),
+            module,
+            None,
+        );
+        if hwnd.0 == 0 {
+            anyhow::bail!("CreateWindowExW failed");
+        }
+        Ok(hwnd)
+    }
+}
+
+unsafe extern "system" fn session_window_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    match msg {
+        WM_QUERYENDSESSION => LRESULT(1),
+        WM_WTSSESSION_CHANGE => {
+            send_wts_session_event(wparam.0 as u32, lparam.0 as u32);
+            LRESULT(0)
+        }
+        WM_ENDSESSION => {
+            if wparam.0 != 0 {
+                finish_session_for_logoff();
+            }
+            LRESULT(0)
+        }
+        _ => DefWindowProcW(hwnd, msg, wparam, lparam),
+    }
+}
+
+/// Lock/unlock and console/RDP transitions explain the gaps and hook oddities they cause.
+fn send_wts_session_event(reason: u32, wts_session_id: u32) {
+    let Some(state) = STATE.get() else {
+        return;
+    };
+    let event_type = match reason {
+        WTS_SESSION_LOCK => "session_lock",
+        WTS_SESSION_UNLOCK => "session_unlock",
+        WTS_CONSOLE_CONNECT => "console_connect",
+        WTS_CONSOLE_DISCONNECT => "console_disconnect",
+        WTS_REMOTE_CONNECT => "remote_connect",
+        WTS_REMOTE_DISCONNECT => "remote_disconnect",
+        _ => return,
+    };
+    if state.paused.load(Ordering::SeqCst) {
+        return;
+    }
+    if matches!(reason, WTS_SESSION_LOCK | WTS_CONSOLE_DISCONNECT | WTS_REMOTE_DISCONNECT) {
+        flush_text_buffer(state, event_type);
+    }
+    send_session_event(state, event_type, json!({ "wts_session_id": wts_session_id }));
+}
+
+/// Windows may terminate the process as soon as WM_ENDSESSION returns, so the text buffer,
+/// session_stop and the writer drain all have to complete inside the handler.
+fn finish_session_for_logoff() {
+    let (Some(state), Some(context)) = (STATE.get(), END_SESSION.get()) else {
+        return;
+    };
+    if context.stop_sent.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    log_line("Windows session ending; flushing recorder.");
+    flush_text_buffer(state, "session_end");
+    context.shutdown.store(true, Ordering::SeqCst);
+    send_session_event(state, "session_stop", json!({ "note": "end_session" }));
+    let deadline = Instant::now() + Duration::from_millis(END_SESSION_WRITER_WAIT_MS);
+    while !context.writer_done.load(Ordering::SeqCst) && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(50));
+    }
+    if !context.writer_done.load(Ordering::SeqCst) {
+        log_line("Writer did not finish before session end.");
+    }
+    let _ = fs::remove_file(&context.lock_path);
+}
+
 fn signal_shutdown(shutdown: &AtomicBool, main_thread_id: u32) {
     shutdown.store(true, Ordering::SeqCst);
     unsafe {
@@ -794,27 +2638,36 @@ fn signal_shutdown(shutdown: &AtomicBool, main_thread_id: u32) {
 }
 
 struct LockInfo {
+    session_id: Option<String>,
     pid: Option<u32>,
+    start_wall_ms: Option<i64>,
+    start_wall_iso: Option<String>,
     raw: Option<String>,
 }
 
 fn read_lock_info(path: &Path) -> Option<LockInfo> {
     let contents = fs::read_to_string(path).ok()?;
-    let mut pid = None;
+    let mut info = LockInfo {
+        session_id: None,
+        pid: None,
+        start_wall_ms: None,
+        start_wall_iso: None,
+        raw: None,
+    };
     for line in contents.lines() {
         let mut parts = line.splitn(2, '=');
         let key = parts.next().unwrap_or("").trim();
         let value = parts.next().unwrap_or("").trim();
-        if key == "pid" {
-            if let Ok(parsed) = value.parse::<u32>() {
-                pid = Some(parsed);
-            }
+        match key {
+            "session_id" if !value.is_empty() => info.session_id = Some(value.to_string()),
+            "pid" => info.pid = value.parse::<u32>().ok(),
+            "start_wall_ms" => info.start_wall_ms = value.parse::<i64>().ok(),
+            "start_wall_iso" if !value.is_empty() => info.start_wall_iso = Some(value.to_string()),
+            _ => {}
         }
     }
-    Some(LockInfo {
-        pid,
-        raw: Some(contents),
-    })
+    info.raw = Some(contents);
+    Some(info)
 }
 
 fn is_pid_running(pid: u32) -> bool {
@@ -865,11 +2718,29 @@ fn load_config(base_dir: &Path, overrides: &CliOverrides) -> Result<RecorderConf
         .config_path
         .clone()
         .unwrap_or_else(|| base_dir.join(CONFIG_FILE));
-    let mut config = load_or_create_config(&config_path)?;
+    let mut config = match load_or_create_config(&config_path) {
+        Ok(config) => config,
+        Err(err) if config_path.exists() => recover_corrupt_config(&config_path, &err),
+        Err(err) => return Err(err),
+    };
     apply_overrides(&mut config, overrides);
     Ok(normalize_config(config))
 }
 
+/// A hand-edit typo should not stop capture: keep a copy of the unreadable file next to it and
+/// start with defaults. Config reloads keep failing quietly instead, so a typo never resets flags mid-session.
+fn recover_corrupt_config(path: &Path, err: &anyhow::Error) -> RecorderConfig {
+    let backup_path = path.with_extension("json.bak");
+    let backup = match fs::copy(path, &backup_path) {
+        Ok(_) => backup_path.display().to_string(),
+        Err(copy_err) => format!("backup failed: {copy_err}"),
+    };
+    let message = format!("Config unreadable ({err:#}); using defaults. Original saved to {backup}.");
+    eprintln!("{message}");
+    log_line(&message);
+    RecorderConfig::default()
+}
+
 fn load_or_create_config(path: &Path) -> Result<RecorderConfig> {
     if path.exists() {
         let contents = fs::read_to_string(path).context("Failed to read config file")?;
@@ -911,12 +2782,17 @@ fn apply_overrides(config: &mut RecorderConfig, overrides: &CliOverrides) {
 fn normalize_config(mut config: RecorderConfig) -> RecorderConfig {
     config.mouse_hz = config.mouse_hz.max(1);
     config.snapshot_hz = config.snapshot_hz.max(1);
+    // Full-window BMPs are large; NaN or negative rates disable the loop.
+    config.self_screenshot_hz = config.self_screenshot_hz.clamp(0.0, 1.0);
     config.clipboard_poll_ms = config.clipboard_poll_ms.max(50);
     config.clipboard_debounce_ms = config.clipboard_debounce_ms.max(50);
     config.clipboard_dedupe_window_ms = config.clipboard_dedupe_window_ms.max(0);
     config.window_poll_hz = config.window_poll_hz.max(0);
     config.window_rect_debounce_ms = config.window_rect_debounce_ms.max(0);
     config.text_flush_ms = config.text_flush_ms.max(250);
+    if config.max_payload_bytes > 0 {
+        config.max_payload_bytes = config.max_payload_bytes.max(256);
+    }
     if config.max_text_len < 16 {
         config.max_text_len = 16;
     }
@@ -932,6 +2808,18 @@ fn normalize_config(mut config: RecorderConfig) -> RecorderConfig {
     }
     config.allowlist_processes = normalize_process_list(config.allowlist_processes);
     config.blocklist_processes = normalize_process_list(config.blocklist_processes);
+    config.clipboard_blocklist_titles = config
+        .clipboard_blocklist_titles
+        .into_iter()
+        .map(|pattern| pattern.trim().to_lowercase())
+        .filter(|pattern| !pattern.is_empty())
+        .collect();
+    config.exclude_event_types = config
+        .exclude_event_types
+        .into_iter()
+        .map(|event_type| event_type.trim().to_string())
+        .filter(|event_type| !event_type.is_empty())
+        .collect();
     config
 }
 
@@ -1009,6 +2897,42 @@ fn parse_mouse_click_mode(value: &str) -> MouseClickMode {
     }
 }
 
+fn parse_hash_algorithm(value: &str) -> HashAlgorithm {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "fnv1a" | "fnv" | "stable" => HashAlgorithm::Fnv1a,
+        _ => HashAlgorithm::Default,
+    }
+}
+
+fn parse_capture_profile(value: &str) -> CaptureProfile {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "focus_only" | "focus" => CaptureProfile::FocusOnly,
+        _ => CaptureProfile::Full,
+    }
+}
+
+fn parse_safe_text_fallback(value: &str) -> SafeTextFallback {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "blocklist_only" | "blocklist" => SafeTextFallback::BlocklistOnly,
+        _ => SafeTextFallback::None,
+    }
+}
+
+fn parse_icon_size(value: &str) -> IconSize {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "extralarge" | "extra_large" | "48" => IconSize::ExtraLarge,
+        "jumbo" | "256" => IconSize::Jumbo,
+        _ => IconSize::Large,
+    }
+}
+
+fn parse_mouse_coord_space(value: &str) -> MouseCoordSpace {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "window_client" => MouseCoordSpace::WindowClient,
+        _ => MouseCoordSpace::Screen,
+    }
+}
+
 fn normalize_process_list(list: Vec<String>) -> Vec<String> {
     list.into_iter()
         .filter_map(|entry| {
@@ -1059,9 +2983,8 @@ fn spawn_stop_watcher(
         while !shutdown.load(Ordering::SeqCst) {
             if stop_path.exists() {
                 let _ = fs::remove_file(&stop_path);
-                flush_text_buffer(&state, "stop_signal");
                 log_line("Stop signal observed; shutting down.");
-                signal_shutdown(&shutdown, main_thread_id);
+                request_stop(&state, &shutdown, main_thread_id);
                 break;
             }
             if !state.paused.load(Ordering::SeqCst) {
@@ -1072,26 +2995,130 @@ fn spawn_stop_watcher(
     })
 }
 
-fn spawn_snapshot_loop(
+struct SnapshotCadence {
+    active_hz: f64,
+    /// None keeps the active rate while idle; Some(0.0) pauses snapshots until input resumes.
+    idle_hz: Option<f64>,
+    idle_after_ms: u64,
+}
+
+impl SnapshotCadence {
+    fn is_idle(&self) -> bool {
+        self.idle_hz.is_some() && user_idle_ms().is_some_and(|idle_ms| idle_ms >= self.idle_after_ms)
+    }
+
+    fn hz(&self, idle: bool) -> f64 {
+        match self.idle_hz {
+            Some(idle_hz) if idle => idle_hz,
+            _ => self.active_hz.max(0.01),
+        }
+    }
+}
+
+fn spawn_snapshot_loop(
+    state: Arc<RecorderState>,
+    shutdown: Arc<AtomicBool>,
+    cadence: SnapshotCadence,
+) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        let poll = Duration::from_millis(250);
+        while !shutdown.load(Ordering::SeqCst) {
+            let idle = cadence.is_idle();
+            let hz = cadence.hz(idle);
+            if state.paused.load(Ordering::SeqCst) || hz <= 0.0 {
+                thread::sleep(poll);
+                continue;
+            }
+            if let Some(snapshot) = build_snapshot(&state) {
+                emit_event(&state, snapshot);
+            }
+            // Sleep in short slices so a long idle interval ends as soon as input returns.
+            let due = Instant::now() + Duration::from_secs_f64(1.0 / hz);
+            while !shutdown.load(Ordering::SeqCst) {
+                let now = Instant::now();
+                if now >= due || (idle && !cadence.is_idle()) {
+                    break;
+                }
+                thread::sleep(poll.min(due - now));
+            }
+        }
+    })
+}
+
+/// System-wide time since the last keyboard or mouse input, independent of the recorder's hooks.
+fn user_idle_ms() -> Option<u64> {
+    let mut info = LASTINPUTINFO {
+        cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+        dwTime: 0,
+    };
+    if !unsafe { GetLastInputInfo(&mut info) }.as_bool() {
+        return None;
+    }
+    Some(unsafe { GetTickCount() }.wrapping_sub(info.dwTime) as u64)
+}
+
+fn spawn_screenshot_loop(
     state: Arc<RecorderState>,
     shutdown: Arc<AtomicBool>,
-    snapshot_hz: u64,
+    screenshot_hz: f64,
+    base_dir: PathBuf,
 ) -> thread::JoinHandle<()> {
     thread::spawn(move || {
-        let interval = Duration::from_millis(1000 / snapshot_hz.max(1));
+        let interval = Duration::from_millis((1000.0 / screenshot_hz) as u64);
+        let tick = Duration::from_millis(250).min(interval);
+        let screenshots_dir = base_dir.join(SCREENSHOTS_DIR);
+        if let Err(err) = fs::create_dir_all(&screenshots_dir) {
+            log_line(&format!("Failed to create screenshots dir: {err}"));
+            return;
+        }
+        let mut last_capture: Option<Instant> = None;
         while !shutdown.load(Ordering::SeqCst) {
-            if state.paused.load(Ordering::SeqCst) {
-                thread::sleep(interval);
-                continue;
-            }
-            if let Some(snapshot) = build_snapshot(&state) {
-                state.sender.try_send(snapshot).ok();
+            let due = last_capture.is_none_or(|at| at.elapsed() >= interval);
+            if due && !state.paused.load(Ordering::SeqCst) {
+                last_capture = Some(Instant::now());
+                if let Some(event) = build_screenshot_event(&state, &screenshots_dir) {
+                    emit_event(&state, event);
+                }
             }
-            thread::sleep(interval);
+            thread::sleep(tick);
         }
     })
 }
 
+fn build_screenshot_event(state: &RecorderState, screenshots_dir: &Path) -> Option<EventRecord> {
+    let (hwnd, window_info) = active_window_info()?;
+    let process_name = window_info.process_name.as_deref();
+    if process_is_blocked(state, process_name) || !process_is_allowed(state, process_name) {
+        return None;
+    }
+    let ts_wall_ms = now_wall_ms();
+    let path = screenshots_dir.join(format!("{}_{}.bmp", state.session_id, ts_wall_ms));
+    let (width, height) = match capture_window_bmp(hwnd, &path) {
+        Ok(size) => size,
+        Err(err) => {
+            log_line(&format!("Screenshot capture failed: {err}"));
+            return None;
+        }
+    };
+    Some(EventRecord {
+        session_id: state.session_id.clone(),
+        ts_wall_ms,
+        ts_mono_ms: now_mono_ms(state),
+        event_type: "screenshot".to_string(),
+        process_name: window_info.process_name.clone(),
+        process_id: window_info.process_id,
+        window_title: Some(window_info.title.clone()),
+        window_class: Some(window_info.class_name.clone()),
+        window_rect: window_info.rect.clone(),
+        mouse: None,
+        payload: json!({
+            "path": path.to_string_lossy(),
+            "width": width,
+            "height": height,
+        }),
+    })
+}
+
 fn spawn_window_poll_loop(
     state: Arc<RecorderState>,
     shutdown: Arc<AtomicBool>,
@@ -1130,12 +3157,16 @@ fn spawn_clipboard_loop(
             let seq = unsafe { GetClipboardSequenceNumber() };
             if seq != last_seq {
                 last_seq = seq;
-                pending_since = Some(Instant::now());
+                let expected = !state.clipboard_on_copy_only
+                    || now_mono_ms(&state) <= state.clipboard_expected_until_ms.load(Ordering::SeqCst);
+                if expected {
+                    pending_since = Some(Instant::now());
+                }
             }
             if let Some(since) = pending_since {
                 if since.elapsed() >= debounce {
                     if let Some(event) = read_clipboard_event(&state, &base_dir) {
-                        state.sender.try_send(event).ok();
+                        emit_event(&state, event);
                     }
                     pending_since = None;
                 }
@@ -1163,7 +3194,9 @@ fn install_hooks() -> Result<(HHOOK, HHOOK)> {
     }
 }
 
-fn install_window_event_hooks() -> Result<(HWINEVENTHOOK, HWINEVENTHOOK)> {
+fn install_window_event_hooks(
+    title_events: bool,
+) -> Result<(HWINEVENTHOOK, HWINEVENTHOOK, Option<HWINEVENTHOOK>)> {
     unsafe {
         let foreground = SetWinEventHook(
             EVENT_SYSTEM_FOREGROUND,
@@ -1192,7 +3225,27 @@ fn install_window_event_hooks() -> Result<(HWINEVENTHOOK, HWINEVENTHOOK)> {
             anyhow::bail!("Failed to install location event hook");
         }
 
-        Ok((foreground, location))
+        let name = if title_events {
+            let hook = SetWinEventHook(
+                EVENT_OBJECT_NAMECHANGE,
+                EVENT_OBJECT_NAMECHANGE,
+                HMODULE::default(),
+                Some(win_event_proc),
+                0,
+                0,
+                WINEVENT_OUTOFCONTEXT,
+            );
+            if hook.0 == 0 {
+                log_line("Failed to install name change event hook; title changes rely on polling.");
+                None
+            } else {
+                Some(hook)
+            }
+        } else {
+            None
+        };
+
+        Ok((foreground, location, name))
     }
 }
 
@@ -1211,6 +3264,7 @@ fn build_snapshot(state: &RecorderState) -> Option<EventRecord> {
         ts_mono_ms: now_mono_ms(state),
         event_type: "snapshot".to_string(),
         process_name: window_info.as_ref().and_then(|info| info.process_name.clone()),
+        process_id: window_info.as_ref().and_then(|info| info.process_id),
         window_title: window_info.as_ref().map(|info| info.title.clone()),
         window_class: window_info.as_ref().map(|info| info.class_name.clone()),
         window_rect: window_info.as_ref().and_then(|info| info.rect.clone()),
@@ -1219,6 +3273,151 @@ fn build_snapshot(state: &RecorderState) -> Option<EventRecord> {
     })
 }
 
+/// Every event passes through here on its way to the writer, so capture-time filtering that
+/// applies to all event types belongs in this function.
+fn emit_event(state: &RecorderState, mut event: EventRecord) {
+    if state.exclude_event_types.contains(event.event_type.as_str()) {
+        return;
+    }
+    if state.queue_near_full.load(Ordering::Relaxed)
+        && matches!(event.event_type.as_str(), "mouse_move" | "snapshot")
+    {
+        state.shed_events.fetch_add(1, Ordering::Relaxed);
+        return;
+    }
+    if state.max_payload_bytes > 0 {
+        cap_payload(&mut event.payload, state.max_payload_bytes);
+    }
+    let typing_counts = (event.event_type == "text_input").then(|| {
+        let count = |key: &str| event.payload.get(key).and_then(Value::as_i64).unwrap_or(0);
+        (count("typed_chars"), count("backspaces"))
+    });
+    let columns = (state.preserialize_events && !HOOK_THREAD.with(Cell::get))
+        .then(|| SerializedColumns::from_event(&event));
+    if columns.is_some() && !state.live_stream_enabled {
+        event.payload = Value::Null;
+    }
+    let queued = QueuedEvent {
+        event,
+        columns,
+        typing_counts,
+    };
+    if state.sender.try_send(queued).is_err() {
+        state.dropped_events.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Shortens the largest string fields until the payload fits in `max_bytes`, then stubs out the
+/// largest arrays/objects, marking the payload with `payload_truncated` when anything was cut.
+/// Sizes come from `json_len_bound`, so nothing is serialized here, and that is safe to run on the
+/// hook thread.
+fn cap_payload(payload: &mut Value, max_bytes: usize) {
+    if json_len_bound(payload) <= max_bytes {
+        return;
+    }
+    if let Some(map) = payload.as_object_mut() {
+        map.insert("payload_truncated".to_string(), json!(true));
+    }
+    loop {
+        let size = json_len_bound(payload);
+        if size <= max_bytes {
+            return;
+        }
+        let longest = longest_string_len(payload);
+        if longest > 0 {
+            // Each removed byte shrinks the bound by at least one byte, escapes by more.
+            shorten_string_of_len(payload, longest, longest.saturating_sub(size - max_bytes));
+        } else if !stub_largest_container(payload) {
+            *payload = json!({ "payload_truncated": true });
+            return;
+        }
+    }
+}
+
+/// Upper bound on the serialized JSON length: exact for strings, integers and literals, and a
+/// float's longest formatting otherwise.
+fn json_len_bound(value: &Value) -> usize {
+    fn string_len(text: &str) -> usize {
+        2 + text
+            .bytes()
+            .map(|byte| match byte {
+                b'"' | b'\\' | b'\n' | b'\r' | b'\t' | 0x08 | 0x0C => 2,
+                0x00..=0x1F => 6,
+                _ => 1,
+            })
+            .sum::<usize>()
+    }
+    let separators = |count: usize| count.saturating_sub(1);
+    match value {
+        Value::Null => 4,
+        Value::Bool(flag) => if *flag { 4 } else { 5 },
+        Value::Number(number) => match (number.as_u64(), number.as_i64()) {
+            (Some(value), _) => value.checked_ilog10().unwrap_or(0) as usize + 1,
+            (None, Some(value)) => value.unsigned_abs().checked_ilog10().unwrap_or(0) as usize + 2,
+            _ => 24,
+        },
+        Value::String(text) => string_len(text),
+        Value::Array(items) => 2 + separators(items.len()) + items.iter().map(json_len_bound).sum::<usize>(),
+        Value::Object(map) => {
+            2 + separators(map.len())
+                + map
+                    .iter()
+                    .map(|(key, item)| string_len(key) + 1 + json_len_bound(item))
+                    .sum::<usize>()
+        }
+    }
+}
+
+/// Replaces the largest array/object directly under the payload with a small stub; false when
+/// there is none bigger than the stub itself.
+fn stub_largest_container(payload: &mut Value) -> bool {
+    let stub = json!({ "truncated": true });
+    let stub_len = json_len_bound(&stub);
+    let children: Vec<&mut Value> = match payload {
+        Value::Array(items) => items.iter_mut().collect(),
+        Value::Object(map) => map.values_mut().collect(),
+        _ => return false,
+    };
+    let largest = children
+        .into_iter()
+        .filter(|child| matches!(child, Value::Array(_) | Value::Object(_)))
+        .map(|child| (json_len_bound(child), child))
+        .filter(|(len, _)| *len > stub_len)
+        .max_by_key(|(len, _)| *len);
+    match largest {
+        Some((_, child)) => {
+            *child = stub;
+            true
+        }
+        None => false,
+    }
+}
+
+fn longest_string_len(value: &Value) -> usize {
+    match value {
+        Value::String(text) => text.len(),
+        Value::Array(items) => items.iter().map(longest_string_len).max().unwrap_or(0),
+        Value::Object(map) => map.values().map(longest_string_len).max().unwrap_or(0),
+        _ => 0,
+    }
+}
+
+fn shorten_string_of_len(value: &mut Value, len: usize, keep: usize) -> bool {
+    match value {
+        Value::String(text) if text.len() == len => {
+            let mut keep = keep.min(text.len());
+            while !text.is_char_boundary(keep) {
+                keep -= 1;
+            }
+            text.truncate(keep);
+            true
+        }
+        Value::Array(items) => items.iter_mut().any(|item| shorten_string_of_len(item, len, keep)),
+        Value::Object(map) => map.values_mut().any(|item| shorten_string_of_len(item, len, keep)),
+        _ => false,
+    }
+}
+
 fn send_session_event(state: &RecorderState, event_type: &str, payload: Value) {
     let event = EventRecord {
         session_id: state.session_id.clone(),
@@ -1226,13 +3425,14 @@ fn send_session_event(state: &RecorderState, event_type: &str, payload: Value) {
         ts_mono_ms: now_mono_ms(state),
         event_type: event_type.to_string(),
         process_name: None,
+        process_id: None,
         window_title: None,
         window_class: None,
         window_rect: None,
         mouse: None,
         payload,
     };
-    state.sender.try_send(event).ok();
+    emit_event(state, event);
 }
 
 fn now_wall_ms() -> i64 {
@@ -1243,7 +3443,7 @@ fn now_wall_ms() -> i64 {
 }
 
 fn now_mono_ms(state: &RecorderState) -> i64 {
-    state.start_instant.elapsed().as_millis() as i64
+    state.mono_offset_ms + state.start_instant.elapsed().as_millis() as i64
 }
 
 #[derive(Clone)]
@@ -1252,6 +3452,7 @@ struct WindowInfo {
     class_name: String,
     rect: Option<RectInfo>,
     process_name: Option<String>,
+    process_id: Option<u32>,
     monitor: Option<MonitorInfo>,
     virtual_desktop_id: Option<String>,
 }
@@ -1259,12 +3460,15 @@ struct WindowInfo {
 struct WindowTracker {
     last_hwnd: HWND,
     last_rect: Option<RectInfo>,
+    last_title: String,
     pending_rect: Option<PendingRect>,
 }
 
 struct PendingRect {
     hwnd: HWND,
     window_info: WindowInfo,
+    /// Rect before the debounced burst began, so the flushed event classifies the whole gesture.
+    start_rect: Option<RectInfo>,
     last_change_ms: i64,
 }
 
@@ -1294,6 +3498,7 @@ fn window_info_for_hwnd(hwnd: HWND) -> Option<WindowInfo> {
     let class_name = get_window_class(hwnd);
     let rect = get_window_rect(hwnd);
     let process_name = get_process_name(hwnd);
+    let process_id = get_process_id(hwnd);
     let monitor = get_monitor_info(hwnd);
     let virtual_desktop_id = get_virtual_desktop_id(hwnd);
     Some(WindowInfo {
@@ -1301,6 +3506,7 @@ fn window_info_for_hwnd(hwnd: HWND) -> Option<WindowInfo> {
         class_name,
         rect,
         process_name,
+        process_id,
         monitor,
         virtual_desktop_id,
     })
@@ -1314,9 +3520,50 @@ fn should_capture_clipboard(state: &RecorderState, window_info: Option<&WindowIn
     if !process_is_allowed(state, process_name) {
         return false;
     }
+    if title_is_clipboard_blocked(state, window_info.map(|info| info.title.as_str())) {
+        return false;
+    }
     true
 }
 
+fn title_is_clipboard_blocked(state: &RecorderState, title: Option<&str>) -> bool {
+    if state.clipboard_blocklist_titles.is_empty() {
+        return false;
+    }
+    let Some(title) = title else {
+        return false;
+    };
+    let title = title.to_lowercase();
+    state
+        .clipboard_blocklist_titles
+        .iter()
+        .any(|pattern| wildcard_match(pattern, &title))
+}
+
+/// Case is folded by the caller; `*` matches any run of characters and `?` exactly one.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = backtrack {
+            p = star_p + 1;
+            t = star_t + 1;
+            backtrack = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|ch| *ch == '*')
+}
+
 fn read_clipboard_event(state: &RecorderState, base_dir: &Path) -> Option<EventRecord> {
     let window_info = active_window_info().map(|(_, info)| info);
     if !should_capture_clipboard(state, window_info.as_ref()) {
@@ -1341,10 +3588,14 @@ fn read_clipboard_event_locked(
     base_dir: &Path,
     window_info: Option<WindowInfo>,
 ) -> Option<EventRecord> {
+    let source = clipboard_source();
+    if title_is_clipboard_blocked(state, source.as_ref().map(|source| source.title.as_str())) {
+        return None;
+    }
     let image = if unsafe { IsClipboardFormatAvailable(CLIPBOARD_CF_DIBV5).is_ok() } {
-        read_clipboard_image(base_dir, CLIPBOARD_CF_DIBV5)
+        read_clipboard_image(base_dir, CLIPBOARD_CF_DIBV5, state.clipboard_image_max_dim, state.hash_algorithm)
     } else if unsafe { IsClipboardFormatAvailable(CLIPBOARD_CF_DIB).is_ok() } {
-        read_clipboard_image(base_dir, CLIPBOARD_CF_DIB)
+        read_clipboard_image(base_dir, CLIPBOARD_CF_DIB, state.clipboard_image_max_dim, state.hash_algorithm)
     } else {
         None
     };
@@ -1355,11 +3606,16 @@ fn read_clipboard_event_locked(
         return Some(build_clipboard_event(
             state,
             window_info,
+            source.as_ref(),
             "clipboard_image",
             json!({
                 "path": image.path,
                 "width": image.width,
                 "height": image.height,
+                "original_width": image.original_width,
+                "original_height": image.original_height,
+                "content_hash": format!("{hash:016x}"),
+                "hash_algorithm": state.hash_algorithm.name(),
             }),
         ));
     }
@@ -1369,6 +3625,7 @@ fn read_clipboard_event_locked(
             return Some(build_clipboard_event(
                 state,
                 window_info,
+                source.as_ref(),
                 "clipboard_files",
                 json!({
                     "files": files,
@@ -1379,10 +3636,14 @@ fn read_clipboard_event_locked(
 
     if unsafe { IsClipboardFormatAvailable(CLIPBOARD_CF_UNICODETEXT).is_ok() } {
         if let Some(text) = read_clipboard_text() {
+            if should_skip_clipboard_text(state, &text) {
+                return None;
+            }
             let trimmed = truncate_text(text, state.max_text_len);
             return Some(build_clipboard_event(
                 state,
                 window_info,
+                source.as_ref(),
                 "clipboard_text",
                 json!({
                     "text": trimmed.text,
@@ -1393,23 +3654,125 @@ fn read_clipboard_event_locked(
         }
     }
 
+    if unsafe { CountClipboardFormats() } == 0 {
+        state.last_clipboard_hash.lock().unwrap().clear();
+        *state.last_clipboard_text_hash.lock().unwrap() = None;
+        return Some(build_clipboard_event(
+            state,
+            window_info,
+            source.as_ref(),
+            "clipboard_cleared",
+            json!({}),
+        ));
+    }
+
+    if state.capture_unknown_clipboard {
+        return Some(build_clipboard_event(
+            state,
+            window_info,
+            source.as_ref(),
+            "clipboard_unknown",
+            json!({
+                "formats": clipboard_format_names(),
+            }),
+        ));
+    }
+
     None
 }
 
+/// Names every format on the open clipboard: registered formats by their registered name,
+/// predefined ones by their CF_ constant and anything else by number.
+fn clipboard_format_names() -> Vec<String> {
+    let mut names = Vec::new();
+    let mut format = 0;
+    loop {
+        format = unsafe { EnumClipboardFormats(format) };
+        if format == 0 {
+            break;
+        }
+        let mut buffer = [0u16; 256];
+        let len = unsafe { GetClipboardFormatNameW(format, &mut buffer) };
+        let name = if len > 0 {
+            String::from_utf16_lossy(&buffer[..len as usize])
+        } else {
+            predefined_clipboard_format_name(format)
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("#{format}"))
+        };
+        names.push(name);
+    }
+    names
+}
+
+fn predefined_clipboard_format_name(format: u32) -> Option<&'static str> {
+    let name = match format {
+        1 => "CF_TEXT",
+        2 => "CF_BITMAP",
+        3 => "CF_METAFILEPICT",
+        4 => "CF_SYLK",
+        5 => "CF_DIF",
+        6 => "CF_TIFF",
+        7 => "CF_OEMTEXT",
+        8 => "CF_DIB",
+        9 => "CF_PALETTE",
+        10 => "CF_PENDATA",
+        11 => "CF_RIFF",
+        12 => "CF_WAVE",
+        13 => "CF_UNICODETEXT",
+        14 => "CF_ENHMETAFILE",
+        15 => "CF_HDROP",
+        16 => "CF_LOCALE",
+        17 => "CF_DIBV5",
+        0x0080 => "CF_OWNERDISPLAY",
+        0x0081 => "CF_DSPTEXT",
+        0x0082 => "CF_DSPBITMAP",
+        0x0083 => "CF_DSPMETAFILEPICT",
+        0x008E => "CF_DSPENHMETAFILE",
+        _ => return None,
+    };
+    Some(name)
+}
+
+struct ClipboardSource {
+    process_name: Option<String>,
+    title: String,
+}
+
+fn clipboard_source() -> Option<ClipboardSource> {
+    let owner = unsafe { GetClipboardOwner() };
+    if owner.0 == 0 {
+        return None;
+    }
+    Some(ClipboardSource {
+        process_name: get_process_name(owner),
+        title: get_window_text(owner),
+    })
+}
+
 fn build_clipboard_event(
     state: &RecorderState,
     window_info: Option<WindowInfo>,
+    source: Option<&ClipboardSource>,
     event_type: &str,
-    payload: Value,
+    mut payload: Value,
 ) -> EventRecord {
-    let (process_name, window_title, window_class, window_rect) = match window_info {
+    if let Some(map) = payload.as_object_mut() {
+        map.insert(
+            "source_process".to_string(),
+            json!(source.and_then(|source| source.process_name.clone())),
+        );
+        map.insert("source_title".to_string(), json!(source.map(|source| source.title.clone())));
+    }
+    let (process_name, process_id, window_title, window_class, window_rect) = match window_info {
         Some(info) => (
             info.process_name.clone(),
+            info.process_id,
             Some(info.title.clone()),
             Some(info.class_name.clone()),
             info.rect.clone(),
         ),
-        None => (None, None, None, None),
+        None => (None, None, None, None, None),
     };
     EventRecord {
         session_id: state.session_id.clone(),
@@ -1417,6 +3780,7 @@ fn build_clipboard_event(
         ts_mono_ms: now_mono_ms(state),
         event_type: event_type.to_string(),
         process_name,
+        process_id,
         window_title,
         window_class,
         window_rect,
@@ -1484,28 +3848,64 @@ fn read_clipboard_files() -> Option<Vec<String>> {
     }
 }
 
+/// Keeps the last `clipboard_hash_history` hashes so toggling A -> B -> A inside the window is
+/// also treated as a repeat, not just an immediate re-copy.
 fn should_skip_clipboard_hash(state: &RecorderState, hash: u64) -> bool {
     let now_ms = now_mono_ms(state);
-    let mut last = state.last_clipboard_hash.lock().unwrap();
-    if let Some(last_hash) = last.as_ref() {
-        if last_hash.hash == hash && now_ms - last_hash.ts_ms <= state.clipboard_dedupe_window_ms {
-            return true;
-        }
+    let mut recent = state.last_clipboard_hash.lock().unwrap();
+    if recent
+        .iter()
+        .any(|entry| entry.hash == hash && now_ms - entry.ts_ms <= state.clipboard_dedupe_window_ms)
+    {
+        return true;
     }
-    *last = Some(ClipboardHash {
+    recent.retain(|entry| entry.hash != hash);
+    recent.push_front(ClipboardHash {
         hash,
         ts_ms: now_ms,
     });
+    recent.truncate(state.clipboard_hash_history);
     false
 }
 
-fn hash_bytes(bytes: &[u8]) -> u64 {
-    let mut hasher = DefaultHasher::new();
-    bytes.hash(&mut hasher);
-    hasher.finish()
+/// Content-based text dedupe: apps that re-set the same text with a different format set bump the
+/// sequence number without changing the text. Repeats keep extending the window so a clipboard
+/// manager re-asserting content every few seconds stays suppressed.
+fn should_skip_clipboard_text(state: &RecorderState, text: &str) -> bool {
+    if state.clipboard_text_dedupe_ms <= 0 {
+        return false;
+    }
+    let normalized = text.trim().replace("\r\n", "\n");
+    let hash = hash_bytes(state.hash_algorithm, normalized.as_bytes());
+    let now_ms = now_mono_ms(state);
+    let mut last = state.last_clipboard_text_hash.lock().unwrap();
+    let skip = last
+        .as_ref()
+        .is_some_and(|last_hash| last_hash.hash == hash && now_ms - last_hash.ts_ms <= state.clipboard_text_dedupe_ms);
+    *last = Some(ClipboardHash { hash, ts_ms: now_ms });
+    skip
+}
+
+fn hash_bytes(algorithm: HashAlgorithm, bytes: &[u8]) -> u64 {
+    match algorithm {
+        HashAlgorithm::Default => {
+            let mut hasher = DefaultHasher::new();
+            bytes.hash(&mut hasher);
+            hasher.finish()
+        }
+        // FNV-1a is fixed by spec, so stored content_hash values stay comparable across builds.
+        HashAlgorithm::Fnv1a => bytes.iter().fold(0xcbf29ce484222325u64, |hash, byte| {
+            (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+        }),
+    }
 }
 
-fn read_clipboard_image(base_dir: &Path, format: u32) -> Option<(ClipboardImage, u64)> {
+fn read_clipboard_image(
+    base_dir: &Path,
+    format: u32,
+    max_dim: u32,
+    algorithm: HashAlgorithm,
+) -> Option<(ClipboardImage, u64)> {
     let handle = unsafe { GetClipboardData(format) }.ok()?;
     let hglobal = HGLOBAL(handle.0 as *mut c_void);
     let size = unsafe { GlobalSize(hglobal) };
@@ -1520,13 +3920,30 @@ fn read_clipboard_image(base_dir: &Path, format: u32) -> Option<(ClipboardImage,
     let _ = unsafe { GlobalUnlock(hglobal) };
 
     let info = parse_dib_info(&bytes)?;
+    let hash = hash_bytes(algorithm, &bytes);
+    let exceeds_cap = max_dim > 0 && (info.width.unsigned_abs() > max_dim || info.height.unsigned_abs() > max_dim);
+    if exceeds_cap {
+        if let Some((path, width, height)) = write_downscaled_clipboard_image(base_dir, &bytes, &info, max_dim) {
+            return Some((
+                ClipboardImage {
+                    path,
+                    width,
+                    height,
+                    original_width: info.width,
+                    original_height: info.height,
+                },
+                hash,
+            ));
+        }
+    }
     let path = write_clipboard_image(base_dir, &bytes, info.image_size)?;
-    let hash = hash_bytes(&bytes);
     Some((
         ClipboardImage {
             path,
             width: info.width,
             height: info.height,
+            original_width: info.width,
+            original_height: info.height,
         },
         hash,
     ))
@@ -1536,6 +3953,9 @@ struct DibInfo {
     width: i32,
     height: i32,
     image_size: usize,
+    bit_count: u16,
+    compression: u32,
+    top_down: bool,
 }
 
 fn parse_dib_info(bytes: &[u8]) -> Option<DibInfo> {
@@ -1549,6 +3969,7 @@ fn parse_dib_info(bytes: &[u8]) -> Option<DibInfo> {
     let width = i32::from_le_bytes(bytes.get(4..8)?.try_into().ok()?);
     let height = i32::from_le_bytes(bytes.get(8..12)?.try_into().ok()?);
     let bit_count = u16::from_le_bytes(bytes.get(14..16)?.try_into().ok()?);
+    let compression = u32::from_le_bytes(bytes.get(16..20)?.try_into().ok()?);
     let size_image = u32::from_le_bytes(bytes.get(20..24)?.try_into().ok()?);
 
     let width_abs = width.abs().max(1) as u32;
@@ -1568,6 +3989,9 @@ fn parse_dib_info(bytes: &[u8]) -> Option<DibInfo> {
         width,
         height: height.abs(),
         image_size,
+        bit_count,
+        compression,
+        top_down: height < 0,
     })
 }
 
@@ -1593,6 +4017,60 @@ fn write_clipboard_image(base_dir: &Path, dib_bytes: &[u8], image_size: usize) -
     Some(path.to_string_lossy().to_string())
 }
 
+/// Nearest-neighbour downscale of uncompressed 24/32-bit DIBs (BI_RGB or BI_BITFIELDS) so the
+/// longer side fits max_dim; other formats return None and are stored at full size.
+fn write_downscaled_clipboard_image(
+    base_dir: &Path,
+    dib_bytes: &[u8],
+    info: &DibInfo,
+    max_dim: u32,
+) -> Option<(String, i32, i32)> {
+    if !matches!(info.bit_count, 24 | 32) || !matches!(info.compression, 0 | 3) {
+        return None;
+    }
+    let src_width = info.width.unsigned_abs() as usize;
+    let src_height = info.height.unsigned_abs() as usize;
+    let bytes_per_pixel = info.bit_count as usize / 8;
+    let src_row = (info.bit_count as usize * src_width).div_ceil(32) * 4;
+    let pixels = dib_bytes.get(dib_bytes.len().checked_sub(info.image_size)?..)?;
+    if pixels.len() < src_row * src_height {
+        return None;
+    }
+
+    let scale = max_dim as f64 / src_width.max(src_height) as f64;
+    let dst_width = ((src_width as f64 * scale).round() as usize).max(1);
+    let dst_height = ((src_height as f64 * scale).round() as usize).max(1);
+    let mut buffer = vec![0u8; dst_width * dst_height * 4];
+    for y in 0..dst_height {
+        let src_y = (y * src_height / dst_height).min(src_height - 1);
+        for x in 0..dst_width {
+            let src_x = (x * src_width / dst_width).min(src_width - 1);
+            let src = src_y * src_row + src_x * bytes_per_pixel;
+            let dst = (y * dst_width + x) * 4;
+            buffer[dst..dst + 3].copy_from_slice(&pixels[src..src + 3]);
+            buffer[dst + 3] = if bytes_per_pixel == 4 { pixels[src + 3] } else { 255 };
+        }
+    }
+
+    // Rows keep the source orientation, so only the sign of biHeight carries over.
+    let height = if info.top_down { -(dst_height as i32) } else { dst_height as i32 };
+    let header = BITMAPINFOHEADER {
+        biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+        biWidth: dst_width as i32,
+        biHeight: height,
+        biPlanes: 1,
+        biBitCount: 32,
+        biCompression: BI_RGB.0,
+        biSizeImage: buffer.len() as u32,
+        ..Default::default()
+    };
+    let dir = base_dir.join(CLIPBOARD_DIR);
+    fs::create_dir_all(&dir).ok()?;
+    let path = dir.join(format!("clipboard_{}_{}.bmp", now_wall_ms(), Uuid::new_v4()));
+    write_bmp_file(&path, &header, &buffer).ok()?;
+    Some((path.to_string_lossy().to_string(), dst_width as i32, dst_height as i32))
+}
+
 struct TruncateResult {
     text: String,
     length: usize,
@@ -1656,13 +4134,108 @@ fn get_window_rect(hwnd: HWND) -> Option<RectInfo> {
     }
 }
 
-fn get_process_name(hwnd: HWND) -> Option<String> {
+/// Emits `process_seen` the first time a process instance (pid plus creation time, so reused pids
+/// count as new) takes the foreground, carrying the command line that tells apart instances
+/// sharing one exe.
+fn note_process_seen(state: &RecorderState, hwnd: HWND, window_info: &WindowInfo) {
+    let process_name = window_info.process_name.as_deref();
+    if process_is_blocked(state, process_name) || !process_is_allowed(state, process_name) {
+        return;
+    }
+    let mut pid: u32 = 0;
     unsafe {
-        let mut pid: u32 = 0;
         GetWindowThreadProcessId(hwnd, Some(&mut pid));
-        if pid == 0 {
+    }
+    if pid == 0 {
+        return;
+    }
+    let Ok(handle) = (unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) }) else {
+        return;
+    };
+    let start_wall_ms = process_start_wall_ms(handle);
+    let is_new = state
+        .seen_processes
+        .lock()
+        .unwrap()
+        .insert((pid, start_wall_ms.unwrap_or(0)));
+    let command_line = if is_new { process_command_line(handle) } else { None };
+    let _ = unsafe { CloseHandle(handle) };
+    if !is_new {
+        return;
+    }
+    let event = EventRecord {
+        session_id: state.session_id.clone(),
+        ts_wall_ms: now_wall_ms(),
+        ts_mono_ms: now_mono_ms(state),
+        event_type: "process_seen".to_string(),
+        process_name: window_info.process_name.clone(),
+        process_id: window_info.process_id,
+        window_title: Some(window_info.title.clone()),
+        window_class: Some(window_info.class_name.clone()),
+        window_rect: window_info.rect.clone(),
+        mouse: None,
+        payload: json!({
+            "pid": pid,
+            "command_line": command_line,
+            "start_wall_ms": start_wall_ms,
+        }),
+    };
+    emit_event(state, event);
+}
+
+fn process_start_wall_ms(handle: HANDLE) -> Option<i64> {
+    let mut creation = FILETIME::default();
+    let mut exit = FILETIME::default();
+    let mut kernel = FILETIME::default();
+    let mut user = FILETIME::default();
+    unsafe { GetProcessTimes(handle, &mut creation, &mut exit, &mut kernel, &mut user) }.ok()?;
+    let ticks = ((creation.dwHighDateTime as i64) << 32) | creation.dwLowDateTime as i64;
+    // FILETIME counts 100ns ticks since 1601-01-01.
+    Some((ticks - 116_444_736_000_000_000) / 10_000)
+}
+
+fn process_command_line(handle: HANDLE) -> Option<String> {
+    // u64 storage keeps the UNICODE_STRING header suitably aligned.
+    let mut buffer = vec![0u64; 512];
+    for _ in 0..2 {
+        let mut needed = 0u32;
+        let status = unsafe {
+            NtQueryInformationProcess(
+                handle,
+                ProcessCommandLineInformation,
+                buffer.as_mut_ptr() as *mut c_void,
+                (buffer.len() * 8) as u32,
+                &mut needed,
+            )
+        };
+        if status.is_ok() {
+            // The buffer starts with a UNICODE_STRING pointing into the rest of the buffer.
+            let unicode = unsafe { &*(buffer.as_ptr() as *const UNICODE_STRING) };
+            if unicode.Buffer.is_null() || unicode.Length == 0 {
+                return None;
+            }
+            let chars = unsafe { std::slice::from_raw_parts(unicode.Buffer.0, unicode.Length as usize / 2) };
+            return Some(String::from_utf16_lossy(chars));
+        }
+        if needed as usize <= buffer.len() * 8 {
             return None;
         }
+        buffer = vec![0u64; (needed as usize).div_ceil(8)];
+    }
+    None
+}
+
+fn get_process_id(hwnd: HWND) -> Option<u32> {
+    let mut pid: u32 = 0;
+    unsafe {
+        GetWindowThreadProcessId(hwnd, Some(&mut pid));
+    }
+    (pid != 0).then_some(pid)
+}
+
+fn get_process_name(hwnd: HWND) -> Option<String> {
+    let pid = get_process_id(hwnd)?;
+    unsafe {
         let handle = match OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) {
             Ok(handle) => handle,
             Err(_) => return None,
@@ -1767,6 +4340,15 @@ fn update_window_events(state: &RecorderState, hwnd: HWND, window_info: WindowIn
     if state.paused.load(Ordering::SeqCst) {
         return;
     }
+    // Dropdowns, tooltips and IME candidate lists briefly take the foreground; ignoring them here
+    // (without touching the tracker) keeps the previous window as the active one.
+    if state.min_window_area_px > 0 {
+        if let Some(rect) = window_info.rect.as_ref() {
+            if (rect.width as i64) * (rect.height as i64) < state.min_window_area_px {
+                return;
+            }
+        }
+    }
     let now_ms = now_mono_ms(state);
     if let Some(new_id) = window_info.virtual_desktop_id.as_deref() {
         let mut last_id = state.last_virtual_desktop_id.lock().unwrap();
@@ -1778,31 +4360,51 @@ fn update_window_events(state: &RecorderState, hwnd: HWND, window_info: WindowIn
             }
         }
     }
-    let (is_new, rect_changed) = {
+    let (is_new, rect_changed, previous_title, previous_rect) = {
         let mut tracker = state.window_tracker.lock().unwrap();
         let is_new = hwnd != tracker.last_hwnd;
         let rect_changed = is_new || window_info.rect != tracker.last_rect;
+        let previous_rect = tracker.last_rect.clone();
+        let previous_title = if !is_new && window_info.title != tracker.last_title {
+            Some(std::mem::replace(&mut tracker.last_title, window_info.title.clone()))
+        } else {
+            tracker.last_title = window_info.title.clone();
+            None
+        };
         tracker.last_hwnd = hwnd;
         tracker.last_rect = window_info.rect.clone();
         if is_new {
             tracker.pending_rect = None;
         }
-        (is_new, rect_changed)
+        (is_new, rect_changed, previous_title, previous_rect)
     };
 
     if is_new {
         flush_text_buffer_with_window(state, Some(window_info.clone()), "window_change");
+        if state.capture_process_info {
+            note_process_seen(state, hwnd, &window_info);
+        }
         send_active_window_changed(state, &window_info);
     }
+    if let Some(previous_title) = previous_title {
+        if state.window_title_events {
+            send_window_title_changed(state, &window_info, &previous_title);
+        }
+    }
     if rect_changed && !is_new {
         if state.window_rect_debounce_ms <= 0 {
-            send_window_rect_changed(state, &window_info);
+            send_window_rect_changed(state, &window_info, previous_rect.as_ref());
             return;
         }
         let mut tracker = state.window_tracker.lock().unwrap();
+        let start_rect = match tracker.pending_rect.take() {
+            Some(pending) if pending.hwnd == hwnd => pending.start_rect,
+            _ => previous_rect,
+        };
         tracker.pending_rect = Some(PendingRect {
             hwnd,
             window_info,
+            start_rect,
             last_change_ms: now_ms,
         });
         return;
@@ -1828,16 +4430,14 @@ fn spawn_window_rect_flush_loop(state: Arc<RecorderState>, shutdown: Arc<AtomicB
                         tracker.pending_rect = None;
                         None
                     } else {
-                        let info = pending.window_info.clone();
-                        tracker.pending_rect = None;
-                        Some(info)
+                        tracker.pending_rect.take()
                     }
                 } else {
                     None
                 }
             };
-            if let Some(info) = pending {
-                send_window_rect_changed(&state, &info);
+            if let Some(pending) = pending {
+                send_window_rect_changed(&state, &pending.window_info, pending.start_rect.as_ref());
             }
             thread::sleep(interval);
         }
@@ -1869,6 +4469,7 @@ fn send_active_window_changed(state: &RecorderState, window_info: &WindowInfo) {
         ts_mono_ms: now_mono_ms(state),
         event_type: "active_window_changed".to_string(),
         process_name: window_info.process_name.clone(),
+        process_id: window_info.process_id,
         window_title: Some(window_info.title.clone()),
         window_class: Some(window_info.class_name.clone()),
         window_rect: window_info.rect.clone(),
@@ -1879,7 +4480,7 @@ fn send_active_window_changed(state: &RecorderState, window_info: &WindowInfo) {
             "virtual_desktop_id": window_info.virtual_desktop_id.clone(),
         }),
     };
-    state.sender.try_send(event).ok();
+    emit_event(state, event);
 }
 
 fn send_virtual_desktop_changed(state: &RecorderState, old_id: &str, new_id: &str) {
@@ -1889,6 +4490,7 @@ fn send_virtual_desktop_changed(state: &RecorderState, old_id: &str, new_id: &st
         ts_mono_ms: now_mono_ms(state),
         event_type: "virtual_desktop_changed".to_string(),
         process_name: None,
+        process_id: None,
         window_title: None,
         window_class: None,
         window_rect: None,
@@ -1898,30 +4500,68 @@ fn send_virtual_desktop_changed(state: &RecorderState, old_id: &str, new_id: &st
             "to": new_id,
         }),
     };
-    state.sender.try_send(event).ok();
+    emit_event(state, event);
+}
+
+/// Title changes on the same hwnd (tab switches, document saves) that focus tracking never sees.
+fn send_window_title_changed(state: &RecorderState, window_info: &WindowInfo, previous_title: &str) {
+    let event = EventRecord {
+        session_id: state.session_id.clone(),
+        ts_wall_ms: now_wall_ms(),
+        ts_mono_ms: now_mono_ms(state),
+        event_type: "window_title_changed".to_string(),
+        process_name: window_info.process_name.clone(),
+        process_id: window_info.process_id,
+        window_title: Some(window_info.title.clone()),
+        window_class: Some(window_info.class_name.clone()),
+        window_rect: window_info.rect.clone(),
+        mouse: None,
+        payload: json!({
+            "from": previous_title,
+            "to": window_info.title.clone(),
+        }),
+    };
+    emit_event(state, event);
+}
+
+fn rect_change_kind(previous: Option<&RectInfo>, current: Option<&RectInfo>) -> Option<&'static str> {
+    let (previous, current) = (previous?, current?);
+    let resized = previous.width != current.width || previous.height != current.height;
+    let moved = previous.left != current.left || previous.top != current.top;
+    match (moved, resized) {
+        (true, false) => Some("move"),
+        (false, true) => Some("resize"),
+        // A left/top edge drag moves the origin as well, so it lands here too.
+        (true, true) => Some("both"),
+        (false, false) => None,
+    }
 }
 
-fn send_window_rect_changed(state: &RecorderState, window_info: &WindowInfo) {
+fn send_window_rect_changed(state: &RecorderState, window_info: &WindowInfo, previous: Option<&RectInfo>) {
     let event = EventRecord {
         session_id: state.session_id.clone(),
         ts_wall_ms: now_wall_ms(),
         ts_mono_ms: now_mono_ms(state),
         event_type: "window_rect_changed".to_string(),
         process_name: window_info.process_name.clone(),
+        process_id: window_info.process_id,
         window_title: Some(window_info.title.clone()),
         window_class: Some(window_info.class_name.clone()),
         window_rect: window_info.rect.clone(),
         mouse: None,
-        payload: json!({}),
+        payload: json!({
+            "change": rect_change_kind(previous, window_info.rect.as_ref()),
+            "previous_rect": previous,
+        }),
     };
-    state.sender.try_send(event).ok();
+    emit_event(state, event);
 }
 
 fn buffer_mouse_scroll(state: &RecorderState, mono_ms: i64, data: &MSLLHOOKSTRUCT, delta: Option<i32>) {
     let delta_value = delta.unwrap_or(0);
     let mut buffer = state.scroll_buffer.lock().unwrap();
     if let Some(existing) = buffer.as_mut() {
-        if mono_ms - existing.last_ts_ms <= 200 {
+        if mono_ms - existing.last_ts_ms <= state.scroll_coalesce_ms {
             existing.last_ts_ms = mono_ms;
             existing.total_delta += delta_value;
             existing.ticks += 1;
@@ -1956,25 +4596,27 @@ fn flush_scroll_buffer(state: &RecorderState, buffer: &mut Option<ScrollBuffer>)
         ts_mono_ms: existing.last_ts_ms,
         event_type: "mouse_scroll".to_string(),
         process_name: None,
+        process_id: None,
         window_title: None,
         window_class: None,
         window_rect: None,
         mouse: Some(mouse),
         payload: json!({ "ticks": existing.ticks, "total_delta": existing.total_delta }),
     };
-    state.sender.try_send(event).ok();
+    emit_event(state, event);
 }
 
 fn send_marker_event(state: &RecorderState, hotkey: &str) {
     let window_info = active_window_info().map(|(_, info)| info);
-    let (process_name, window_title, window_class, window_rect) = match window_info {
+    let (process_name, process_id, window_title, window_class, window_rect) = match window_info {
         Some(info) => (
             info.process_name.clone(),
+            info.process_id,
             Some(info.title.clone()),
             Some(info.class_name.clone()),
             info.rect.clone(),
         ),
-        None => (None, None, None, None),
+        None => (None, None, None, None, None),
     };
     let event = EventRecord {
         session_id: state.session_id.clone(),
@@ -1982,6 +4624,7 @@ fn send_marker_event(state: &RecorderState, hotkey: &str) {
         ts_mono_ms: now_mono_ms(state),
         event_type: "marker".to_string(),
         process_name,
+        process_id,
         window_title,
         window_class,
         window_rect,
@@ -1990,7 +4633,7 @@ fn send_marker_event(state: &RecorderState, hotkey: &str) {
             "hotkey": hotkey,
         }),
     };
-    state.sender.try_send(event).ok();
+    emit_event(state, event);
 }
 
 unsafe extern "system" fn win_event_proc(
@@ -2017,7 +4660,7 @@ unsafe extern "system" fn win_event_proc(
                 update_window_events(state, hwnd, window_info);
             }
         }
-        EVENT_OBJECT_LOCATIONCHANGE => {
+        EVENT_OBJECT_LOCATIONCHANGE | EVENT_OBJECT_NAMECHANGE => {
             let foreground = GetForegroundWindow();
             if hwnd != foreground {
                 return;
@@ -2030,9 +4673,40 @@ unsafe extern "system" fn win_event_proc(
     }
 }
 
+fn track_mouse_buttons(state: &RecorderState, message: u32) {
+    let (bit, down) = match message {
+        WM_LBUTTONDOWN => (MOUSE_BUTTON_LEFT, true),
+        WM_LBUTTONUP => (MOUSE_BUTTON_LEFT, false),
+        WM_RBUTTONDOWN => (MOUSE_BUTTON_RIGHT, true),
+        WM_RBUTTONUP => (MOUSE_BUTTON_RIGHT, false),
+        WM_MBUTTONDOWN => (MOUSE_BUTTON_MIDDLE, true),
+        WM_MBUTTONUP => (MOUSE_BUTTON_MIDDLE, false),
+        _ => return,
+    };
+    if down {
+        state.mouse_buttons_down.fetch_or(bit, Ordering::SeqCst);
+    } else {
+        state.mouse_buttons_down.fetch_and(!bit, Ordering::SeqCst);
+    }
+}
+
+fn held_mouse_buttons(state: &RecorderState) -> Vec<&'static str> {
+    let mask = state.mouse_buttons_down.load(Ordering::SeqCst);
+    [
+        (MOUSE_BUTTON_LEFT, "left"),
+        (MOUSE_BUTTON_RIGHT, "right"),
+        (MOUSE_BUTTON_MIDDLE, "middle"),
+    ]
+    .into_iter()
+    .filter(|(bit, _)| mask & bit != 0)
+    .map(|(_, name)| name)
+    .collect()
+}
+
 unsafe extern "system" fn mouse_hook_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
     if code == 0 {
         if let Some(state) = STATE.get() {
+            track_mouse_buttons(state, wparam.0 as u32);
             if state.paused.load(Ordering::SeqCst) {
                 return CallNextHookEx(None, code, wparam, lparam);
             }
@@ -2068,6 +4742,9 @@ unsafe extern "system" fn mouse_hook_proc(code: i32, wparam: WPARAM, lparam: LPA
                         wparam.0 as u32,
                         WM_LBUTTONUP | WM_RBUTTONUP | WM_MBUTTONUP
                     );
+                    if state.mouse_click_debounce_ms > 0 && is_click_bounce(state, wparam.0 as u32, is_down) {
+                        return CallNextHookEx(HHOOK(0), code, wparam, lparam);
+                    }
                     let allow = match state.mouse_click_mode {
                         MouseClickMode::Down => is_down,
                         MouseClickMode::Up => is_up,
@@ -2090,12 +4767,32 @@ unsafe extern "system" fn mouse_hook_proc(code: i32, wparam: WPARAM, lparam: LPA
                     if last >= 0 && mono_ms - last < state.mouse_move_interval_ms {
                         return CallNextHookEx(HHOOK(0), code, wparam, lparam);
                     }
+                    if state.mouse_move_min_distance_px > 0 {
+                        let mut last_pos = state.last_mouse_move_pos.lock().unwrap();
+                        if let Some((last_x, last_y)) = *last_pos {
+                            let dx = (data.pt.x - last_x) as i64;
+                            let dy = (data.pt.y - last_y) as i64;
+                            let min_distance = state.mouse_move_min_distance_px;
+                            if dx * dx + dy * dy < min_distance * min_distance
+                                && mono_ms - last < state.mouse_move_gap_ms
+                            {
+                                return CallNextHookEx(HHOOK(0), code, wparam, lparam);
+                            }
+                        }
+                        *last_pos = Some((data.pt.x, data.pt.y));
+                    }
                     state.last_mouse_move_ms.store(mono_ms, Ordering::SeqCst);
                 }
 
+                let (x, y, mut payload) = translate_mouse_point(state, data.pt.x, data.pt.y);
+                if event_type == "mouse_move" && state.mouse_move_buttons {
+                    if let Some(map) = payload.as_object_mut() {
+                        map.insert("buttons".to_string(), json!(held_mouse_buttons(state)));
+                    }
+                }
                 let mouse = MouseInfo {
-                    x: data.pt.x,
-                    y: data.pt.y,
+                    x,
+                    y,
                     button: button.map(|b| b.to_string()),
                     delta,
                 };
@@ -2105,19 +4802,54 @@ unsafe extern "system" fn mouse_hook_proc(code: i32, wparam: WPARAM, lparam: LPA
                     ts_mono_ms: mono_ms,
                     event_type: event_type.to_string(),
                     process_name: None,
+                    process_id: None,
                     window_title: None,
                     window_class: None,
                     window_rect: None,
                     mouse: Some(mouse),
-                    payload: json!({}),
+                    payload,
                 };
-                state.sender.try_send(event).ok();
+                emit_event(state, event);
             }
         }
     }
     CallNextHookEx(HHOOK(0), code, wparam, lparam)
 }
 
+/// Switch-bounce suppression: a same-button down inside the debounce window after the previous
+/// down is dropped, together with the up that ends it.
+fn is_click_bounce(state: &RecorderState, message: u32, is_down: bool) -> bool {
+    let index = match message {
+        WM_LBUTTONDOWN | WM_LBUTTONUP => 0,
+        WM_RBUTTONDOWN | WM_RBUTTONUP => 1,
+        _ => 2,
+    };
+    if !is_down {
+        return state.bounced_button_up[index].swap(false, Ordering::SeqCst);
+    }
+    let now_ms = now_mono_ms(state);
+    let last_ms = state.last_button_down_ms[index].swap(now_ms, Ordering::SeqCst);
+    let is_bounce = now_ms.saturating_sub(last_ms) < state.mouse_click_debounce_ms;
+    state.bounced_button_up[index].store(is_bounce, Ordering::SeqCst);
+    is_bounce
+}
+
+fn translate_mouse_point(state: &RecorderState, x: i32, y: i32) -> (i32, i32, Value) {
+    if state.mouse_coord_space == MouseCoordSpace::Screen {
+        return (x, y, json!({}));
+    }
+    let hwnd = unsafe { GetForegroundWindow() };
+    let mut pt = POINT { x, y };
+    if hwnd.0 != 0 && unsafe { ScreenToClient(hwnd, &mut pt) }.as_bool() {
+        return (
+            pt.x,
+            pt.y,
+            json!({ "coord_space": "window_client", "screen_x": x, "screen_y": y }),
+        );
+    }
+    (x, y, json!({ "coord_space": "screen", "coord_fallback": true }))
+}
+
 unsafe extern "system" fn keyboard_hook_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
     if code == 0 {
         if let Some(state) = STATE.get() {
@@ -2157,13 +4889,31 @@ unsafe extern "system" fn keyboard_hook_proc(code: i32, wparam: WPARAM, lparam:
                     || pressed.contains(&(VK_RMENU.0 as u32));
                 let has_win =
                     pressed.contains(&(VK_LWIN.0 as u32)) || pressed.contains(&(VK_RWIN.0 as u32));
+                let now_ms = now_mono_ms(state);
+                // Keys landing right after a Ctrl/Alt/Win chord (or the release of its modifiers) are
+                // likely shortcut tails that raced the modifier up event, so keep them out of the text
+                // buffer. A lone modifier tap is not a chord and does not arm the guard.
+                let in_shortcut_guard =
+                    now_ms.saturating_sub(state.last_chord_ms.load(Ordering::SeqCst)) < state.shortcut_guard_ms;
+                if is_down && !is_modifier && (has_ctrl || has_alt || has_win) {
+                    state.last_chord_ms.store(now_ms, Ordering::SeqCst);
+                    state.chord_modifiers_held.store(true, Ordering::SeqCst);
+                }
+                if is_up && is_shortcut_modifier(vk) && state.chord_modifiers_held.load(Ordering::SeqCst) {
+                    state.last_chord_ms.store(now_ms, Ordering::SeqCst);
+                    if !(has_ctrl || has_alt || has_win) {
+                        state.chord_modifiers_held.store(false, Ordering::SeqCst);
+                    }
+                }
                 if is_down && is_chorded {
+                    let (physical_vk, key_label) = physical_key(data.scanCode, data.flags.0);
                     let event = EventRecord {
                         session_id: state.session_id.clone(),
                         ts_wall_ms: now_wall_ms(),
                         ts_mono_ms: now_mono_ms(state),
                         event_type: "key_shortcut".to_string(),
                         process_name: None,
+                        process_id: None,
                         window_title: None,
                         window_class: None,
                         window_rect: None,
@@ -2174,9 +4924,26 @@ unsafe extern "system" fn keyboard_hook_proc(code: i32, wparam: WPARAM, lparam:
                             "repeat": is_repeat,
                             "injected": is_injected,
                             "scan_code": data.scanCode,
+                            "physical_vk": physical_vk,
+                            "key_label": key_label,
                         }),
                     };
-                    state.sender.try_send(event).ok();
+                    emit_event(state, event);
+                }
+
+                let is_copy_chord = is_down
+                    && !has_alt
+                    && !has_win
+                    && ((has_ctrl && matches!(vk, 0x43 | 0x58 | 0x2D)) || (has_shift && !has_ctrl && vk == 0x2E));
+                if is_copy_chord && state.clipboard_on_copy_only {
+                    state
+                        .clipboard_expected_until_ms
+                        .store(now_ms + state.clipboard_copy_window_ms, Ordering::SeqCst);
+                }
+                if is_copy_chord && !is_repeat {
+                    if let Some(requests) = state.selection_requests.as_ref() {
+                        requests.try_send(()).ok();
+                    }
                 }
 
                 let is_marker_hotkey =
@@ -2186,7 +4953,7 @@ unsafe extern "system" fn keyboard_hook_proc(code: i32, wparam: WPARAM, lparam:
                     send_marker_event(state, "Ctrl+0");
                 }
 
-                if is_down && !is_modifier && !has_ctrl && !has_alt && !has_win {
+                if is_down && !is_modifier && !has_ctrl && !has_alt && !has_win && !in_shortcut_guard {
                     let window_info = active_window_info().map(|(_, info)| info);
                     if !should_capture_text(state, window_info.as_ref()) {
                         flush_text_buffer_with_window(state, window_info, "unsafe_target");
@@ -2199,13 +4966,17 @@ unsafe extern "system" fn keyboard_hook_proc(code: i32, wparam: WPARAM, lparam:
                     && ((is_down && state.raw_keys_mode != RawKeysMode::Up)
                         || (is_up && state.raw_keys_mode != RawKeysMode::Down))
                     && !(state.suppress_raw_keys_on_shortcut && is_chorded)
+                    && !(state.suppress_key_repeats && is_repeat)
+                    && !state.raw_keys_exclude_vks.contains(&vk)
                 {
+                    let (physical_vk, key_label) = physical_key(data.scanCode, data.flags.0);
                     let event = EventRecord {
                         session_id: state.session_id.clone(),
                         ts_wall_ms: now_wall_ms(),
                         ts_mono_ms: now_mono_ms(state),
                         event_type: if is_down { "key_down" } else { "key_up" }.to_string(),
                         process_name: None,
+                        process_id: None,
                         window_title: None,
                         window_class: None,
                         window_rect: None,
@@ -2216,9 +4987,11 @@ unsafe extern "system" fn keyboard_hook_proc(code: i32, wparam: WPARAM, lparam:
                             "repeat": is_repeat,
                             "injected": is_injected,
                             "scan_code": data.scanCode,
+                            "physical_vk": physical_vk,
+                            "key_label": key_label,
                         }),
                     };
-                    state.sender.try_send(event).ok();
+                    emit_event(state, event);
                 }
             }
         }
@@ -2226,6 +4999,20 @@ unsafe extern "system" fn keyboard_hook_proc(code: i32, wparam: WPARAM, lparam:
     CallNextHookEx(HHOOK(0), code, wparam, lparam)
 }
 
+fn is_shortcut_modifier(vk: u32) -> bool {
+    matches!(
+        vk,
+        x if x == VK_CONTROL.0 as u32
+            || x == VK_LCONTROL.0 as u32
+            || x == VK_RCONTROL.0 as u32
+            || x == VK_MENU.0 as u32
+            || x == VK_LMENU.0 as u32
+            || x == VK_RMENU.0 as u32
+            || x == VK_LWIN.0 as u32
+            || x == VK_RWIN.0 as u32
+    )
+}
+
 fn current_modifiers(pressed: &HashSet<u32>) -> Vec<String> {
     let mut mods = Vec::new();
     if pressed.contains(&(VK_CONTROL.0 as u32))
@@ -2266,6 +5053,20 @@ fn is_modifier_key(vk: u32) -> bool {
     )
 }
 
+/// Identifies the physical key from its scan code: the extended vk it maps to and the keyboard's
+/// own label (e.g. "Num Enter"), so OEM punctuation can be compared across layouts.
+fn physical_key(scan_code: u32, flags: u32) -> (Option<u32>, Option<String>) {
+    let is_extended = (flags & 0x01) != 0;
+    let scan = if is_extended { scan_code | 0xE000 } else { scan_code };
+    let mapped = unsafe { MapVirtualKeyW(scan, MAPVK_VSC_TO_VK_EX) };
+    let physical_vk = (mapped != 0).then_some(mapped);
+    let lparam = ((scan_code & 0xFF) << 16) | if is_extended { 1 << 24 } else { 0 };
+    let mut buffer = [0u16; 64];
+    let written = unsafe { GetKeyNameTextW(lparam as i32, &mut buffer) };
+    let key_label = (written > 0).then(|| String::from_utf16_lossy(&buffer[..written as usize]));
+    (physical_vk, key_label)
+}
+
 fn vk_to_name(vk: u32) -> String {
     match vk {
         0x08 => "Backspace".to_string(),
@@ -2309,7 +5110,8 @@ fn should_capture_text(state: &RecorderState, window_info: Option<&WindowInfo>)
         return true;
     }
     let Some(uia) = get_uia() else {
-        return false;
+        report_uia_unavailable(state);
+        return state.safe_text_fallback == SafeTextFallback::BlocklistOnly && !focused_edit_is_password();
     };
     let element = match unsafe { uia.GetFocusedElement() } {
         Ok(element) => element,
@@ -2336,6 +5138,32 @@ fn should_capture_text(state: &RecorderState, window_info: Option<&WindowInfo>)
     true
 }
 
+fn report_uia_unavailable(state: &RecorderState) {
+    if state.uia_unavailable_reported.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let fallback = match state.safe_text_fallback {
+        SafeTextFallback::None => "none",
+        SafeTextFallback::BlocklistOnly => "blocklist_only",
+    };
+    log_line(&format!("UI Automation unavailable; safe text fallback is {fallback}."));
+    send_session_event(state, "uia_unavailable", json!({ "safe_text_fallback": fallback }));
+}
+
+/// Without UIA only classic Win32 edit controls can be recognised as password fields, via the
+/// ES_PASSWORD style on the foreground thread's focus window.
+fn focused_edit_is_password() -> bool {
+    let mut info = GUITHREADINFO {
+        cbSize: std::mem::size_of::<GUITHREADINFO>() as u32,
+        ..Default::default()
+    };
+    if unsafe { GetGUIThreadInfo(0, &mut info) }.is_err() || info.hwndFocus.0 == 0 {
+        return false;
+    }
+    let style = unsafe { GetWindowLongW(info.hwndFocus, GWL_STYLE) };
+    style & EDIT_STYLE_PASSWORD != 0 && get_window_class(info.hwndFocus).eq_ignore_ascii_case("edit")
+}
+
 fn snapshot_text_from_uia(_state: &RecorderState) -> Option<String> {
     let Some(uia) = get_uia() else {
         return None;
@@ -2373,6 +5201,99 @@ fn snapshot_text_from_uia(_state: &RecorderState) -> Option<String> {
     }
 }
 
+/// UIA calls can block on a busy target app, so copy chords from the keyboard hook only queue
+/// a request and this thread (with its own COM apartment) reads the selection.
+fn spawn_selection_worker(
+    state: Arc<RecorderState>,
+    shutdown: Arc<AtomicBool>,
+    requests: Receiver<()>,
+) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        let _com_guard = ComGuard::new(false);
+        while !shutdown.load(Ordering::SeqCst) {
+            if requests.recv_timeout(Duration::from_millis(250)).is_err() {
+                continue;
+            }
+            if state.paused.load(Ordering::SeqCst) {
+                continue;
+            }
+            let window_info = active_window_info().map(|(_, info)| info);
+            if !should_capture_text(&state, window_info.as_ref()) {
+                continue;
+            }
+            if let Some(text) = selection_text_from_uia() {
+                send_selection_event(&state, window_info, text);
+            }
+        }
+    })
+}
+
+fn selection_text_from_uia() -> Option<String> {
+    let uia = get_uia()?;
+    let element = unsafe { uia.GetFocusedElement() }.ok()?;
+    let is_password = unsafe { element.CurrentIsPassword() }
+        .ok()
+        .map(|value| value.as_bool())
+        .unwrap_or(false);
+    if is_password {
+        return None;
+    }
+    let pattern: IUIAutomationTextPattern = unsafe { element.GetCurrentPatternAs(UIA_TextPatternId) }.ok()?;
+    let ranges = unsafe { pattern.GetSelection() }.ok()?;
+    let count = unsafe { ranges.Length() }.unwrap_or(0);
+    let mut parts = Vec::new();
+    for index in 0..count {
+        let Ok(range) = (unsafe { ranges.GetElement(index) }) else {
+            continue;
+        };
+        if let Ok(text) = unsafe { range.GetText(-1) } {
+            let text = String::from_utf16_lossy(text.as_wide());
+            if !text.is_empty() {
+                parts.push(text);
+            }
+        }
+    }
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join("\n"))
+    }
+}
+
+fn send_selection_event(state: &RecorderState, window_info: Option<WindowInfo>, text: String) {
+    let (process_name, process_id, window_title, window_class, window_rect) = match window_info {
+        Some(info) => (
+            info.process_name.clone(),
+            info.process_id,
+            Some(info.title.clone()),
+            Some(info.class_name.clone()),
+            info.rect.clone(),
+        ),
+        None => (None, None, None, None, None),
+    };
+    let selection = truncate_text(text, state.max_text_len);
+    let event = EventRecord {
+        session_id: state.session_id.clone(),
+        ts_wall_ms: now_wall_ms(),
+        ts_mono_ms: now_mono_ms(state),
+        event_type: "text_selection".to_string(),
+        process_name,
+        process_id,
+        window_title,
+        window_class,
+        window_rect,
+        mouse: None,
+        payload: json!({
+            "text": selection.text,
+            "length": selection.length,
+            "truncated": selection.truncated,
+            "trigger": "copy_chord",
+            "source": "uia",
+        }),
+    };
+    emit_event(state, event);
+}
+
 fn process_is_allowed(state: &RecorderState, process_name: Option<&str>) -> bool {
     if state.allowlist_processes.is_empty() {
         return true;
@@ -2413,39 +5334,98 @@ fn ensure_app_icon(state: &RecorderState, process_path: &str) -> Option<String>
     if process_path.is_empty() {
         return None;
     }
+    let modified_ms = if state.icon_recapture {
+        file_modified_ms(Path::new(process_path))
+    } else {
+        None
+    };
     {
         let cache = state.app_icon_cache.lock().unwrap();
-        if let Some(path) = cache.get(process_path) {
-            return Some(path.clone());
+        if let Some(cached) = cache.get(process_path) {
+            if !state.icon_recapture || cached.modified_ms == modified_ms {
+                return Some(cached.path.clone());
+            }
         }
     }
 
     let hash = hash_process_name(process_path);
-    let icon_path = state.icons_dir.join(format!("{hash}.bmp"));
-    if icon_path.exists() {
-        let mut cache = state.app_icon_cache.lock().unwrap();
-        let path_string = icon_path.to_string_lossy().to_string();
-        cache.insert(process_path.to_string(), path_string.clone());
-        return Some(path_string);
-    }
-
-    if capture_icon_bmp(process_path, &icon_path).is_ok() {
+    // Versioned names keep the old icon on disk for events recorded before the exe changed.
+    let size_suffix = match state.icon_size {
+        IconSize::Large => "",
+        IconSize::ExtraLarge => "_48",
+        IconSize::Jumbo => "_256",
+    };
+    let file_name = match modified_ms {
+        Some(ms) => format!("{hash}_{ms}{size_suffix}.bmp"),
+        None => format!("{hash}{size_suffix}.bmp"),
+    };
+    let icon_path = state.icons_dir.join(file_name);
+    if icon_path.exists() || capture_icon_bmp(process_path, &icon_path, state.icon_size).is_ok() {
         let mut cache = state.app_icon_cache.lock().unwrap();
         let path_string = icon_path.to_string_lossy().to_string();
-        cache.insert(process_path.to_string(), path_string.clone());
+        cache.insert(
+            process_path.to_string(),
+            CachedIcon {
+                path: path_string.clone(),
+                modified_ms,
+            },
+        );
         return Some(path_string);
     }
 
     None
 }
 
-fn capture_icon_bmp(process_path: &str, icon_path: &Path) -> Result<()> {
+fn file_modified_ms(path: &Path) -> Option<i64> {
+    let modified = fs::metadata(path).and_then(|meta| meta.modified()).ok()?;
+    Some(modified.duration_since(UNIX_EPOCH).ok()?.as_millis() as i64)
+}
+
+fn capture_icon_bmp(process_path: &str, icon_path: &Path, size: IconSize) -> Result<()> {
     let mut wide: Vec<u16> = process_path.encode_utf16().collect();
     wide.push(0);
+    let hicon = match size {
+        IconSize::Large => None,
+        IconSize::ExtraLarge => system_image_list_icon(&wide, SHIL_EXTRALARGE),
+        IconSize::Jumbo => system_image_list_icon(&wide, SHIL_JUMBO),
+    };
+    let hicon = match hicon {
+        Some(hicon) => hicon,
+        None => shell_large_icon(&wide)?,
+    };
+    write_icon_bmp(hicon, icon_path)
+}
+
+/// Icons above 32px only come from the system image lists; returns `None` so callers can fall
+/// back to `SHGFI_LARGEICON`.
+fn system_image_list_icon(wide_path: &[u16], image_list: u32) -> Option<HICON> {
+    let mut info = SHFILEINFOW::default();
+    let result = unsafe {
+        SHGetFileInfoW(
+            PCWSTR(wide_path.as_ptr()),
+            FILE_FLAGS_AND_ATTRIBUTES(0),
+            Some(&mut info),
+            std::mem::size_of::<SHFILEINFOW>() as u32,
+            SHGFI_SYSICONINDEX,
+        )
+    };
+    if result == 0 {
+        return None;
+    }
+    let list: IImageList = unsafe { SHGetImageList(image_list as i32) }.ok()?;
+    let hicon = unsafe { list.GetIcon(info.iIcon, ILD_TRANSPARENT.0) }.ok()?;
+    if hicon.0 == 0 {
+        None
+    } else {
+        Some(hicon)
+    }
+}
+
+fn shell_large_icon(wide_path: &[u16]) -> Result<HICON> {
     let mut info = SHFILEINFOW::default();
     let result = unsafe {
         SHGetFileInfoW(
-            PCWSTR(wide.as_ptr()),
+            PCWSTR(wide_path.as_ptr()),
             FILE_FLAGS_AND_ATTRIBUTES(0),
             Some(&mut info),
             std::mem::size_of::<SHFILEINFOW>() as u32,
@@ -2455,12 +5435,13 @@ fn capture_icon_bmp(process_path: &str, icon_path: &Path) -> Result<()> {
     if result == 0 {
         anyhow::bail!("SHGetFileInfoW failed");
     }
-
-    let hicon = info.hIcon;
-    if hicon.0 == 0 {
+    if info.hIcon.0 == 0 {
         anyhow::bail!("No icon handle");
     }
+    Ok(info.hIcon)
+}
 
+fn write_icon_bmp(hicon: HICON, icon_path: &Path) -> Result<()> {
     let mut icon_info = ICONINFO::default();
     unsafe {
         if GetIconInfo(hicon, &mut icon_info).is_err() {
@@ -2529,11 +5510,15 @@ fn capture_icon_bmp(process_path: &str, icon_path: &Path) -> Result<()> {
         anyhow::bail!("GetDIBits failed");
     }
 
+    write_bmp_file(icon_path, &bmi.bmiHeader, &buffer).context("Failed to write icon file")
+}
+
+fn write_bmp_file(path: &Path, header: &BITMAPINFOHEADER, buffer: &[u8]) -> Result<()> {
     let file_header_size = 14u32;
     let info_header_size = std::mem::size_of::<BITMAPINFOHEADER>() as u32;
-    let file_size = file_header_size + info_header_size + data_len as u32;
+    let file_size = file_header_size + info_header_size + buffer.len() as u32;
 
-    let mut file = fs::File::create(icon_path).context("Failed to create icon file")?;
+    let mut file = fs::File::create(path)?;
     file.write_all(&[
         0x42, 0x4d,
         (file_size & 0xFF) as u8,
@@ -2545,50 +5530,121 @@ fn capture_icon_bmp(process_path: &str, icon_path: &Path) -> Result<()> {
         0, 0, 0,
     ])?;
 
-    file.write_all(&bmi.bmiHeader.biSize.to_le_bytes())?;
-    file.write_all(&bmi.bmiHeader.biWidth.to_le_bytes())?;
-    file.write_all(&bmi.bmiHeader.biHeight.to_le_bytes())?;
-    file.write_all(&bmi.bmiHeader.biPlanes.to_le_bytes())?;
-    file.write_all(&bmi.bmiHeader.biBitCount.to_le_bytes())?;
-    file.write_all(&bmi.bmiHeader.biCompression.to_le_bytes())?;
-    file.write_all(&bmi.bmiHeader.biSizeImage.to_le_bytes())?;
-    file.write_all(&bmi.bmiHeader.biXPelsPerMeter.to_le_bytes())?;
-    file.write_all(&bmi.bmiHeader.biYPelsPerMeter.to_le_bytes())?;
-    file.write_all(&bmi.bmiHeader.biClrUsed.to_le_bytes())?;
-    file.write_all(&bmi.bmiHeader.biClrImportant.to_le_bytes())?;
-    file.write_all(&buffer)?;
+    file.write_all(&header.biSize.to_le_bytes())?;
+    file.write_all(&header.biWidth.to_le_bytes())?;
+    file.write_all(&header.biHeight.to_le_bytes())?;
+    file.write_all(&header.biPlanes.to_le_bytes())?;
+    file.write_all(&header.biBitCount.to_le_bytes())?;
+    file.write_all(&header.biCompression.to_le_bytes())?;
+    file.write_all(&header.biSizeImage.to_le_bytes())?;
+    file.write_all(&header.biXPelsPerMeter.to_le_bytes())?;
+    file.write_all(&header.biYPelsPerMeter.to_le_bytes())?;
+    file.write_all(&header.biClrUsed.to_le_bytes())?;
+    file.write_all(&header.biClrImportant.to_le_bytes())?;
+    file.write_all(buffer)?;
     Ok(())
 }
 
+fn capture_window_bmp(hwnd: HWND, path: &Path) -> Result<(i32, i32)> {
+    let mut rect = RECT::default();
+    unsafe { GetWindowRect(hwnd, &mut rect) }.context("GetWindowRect failed")?;
+    let width = rect.right - rect.left;
+    let height = rect.bottom - rect.top;
+    if width <= 0 || height <= 0 {
+        anyhow::bail!("Invalid window size");
+    }
+
+    let window_dc = unsafe { GetWindowDC(hwnd) };
+    if window_dc.is_invalid() {
+        anyhow::bail!("GetWindowDC failed");
+    }
+    let mem_dc = unsafe { CreateCompatibleDC(window_dc) };
+    let bitmap = unsafe { CreateCompatibleBitmap(window_dc, width, height) };
+    let old_obj = unsafe { SelectObject(mem_dc, bitmap) };
+    let copied = unsafe { PrintWindow(hwnd, mem_dc, PW_RENDERFULLCONTENT) }.as_bool();
+
+    let mut bmi = BITMAPINFO {
+        bmiHeader: BITMAPINFOHEADER {
+            biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+            biWidth: width,
+            biHeight: height,
+            biPlanes: 1,
+            biBitCount: 32,
+            biCompression: BI_RGB.0,
+            biSizeImage: (width * height * 4) as u32,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut buffer = vec![0u8; (width * height * 4) as usize];
+    let scanlines = if copied {
+        unsafe {
+            SelectObject(mem_dc, old_obj);
+            GetDIBits(
+                mem_dc,
+                bitmap,
+                0,
+                height as u32,
+                Some(buffer.as_mut_ptr() as *mut c_void),
+                &mut bmi,
+                DIB_RGB_COLORS,
+            )
+        }
+    } else {
+        unsafe {
+            SelectObject(mem_dc, old_obj);
+        }
+        0
+    };
+    unsafe {
+        DeleteObject(bitmap);
+        DeleteDC(mem_dc);
+        ReleaseDC(hwnd, window_dc);
+    }
+    if scanlines == 0 {
+        anyhow::bail!("Window capture failed");
+    }
+
+    write_bmp_file(path, &bmi.bmiHeader, &buffer).context("Failed to write screenshot file")?;
+    Ok((width, height))
+}
+
 fn handle_text_key(state: &RecorderState, window_info: Option<WindowInfo>, vk: u32, scan_code: u32) {
     let now_ms = now_mono_ms(state);
     flush_text_buffer_if_stale_with_window(state, window_info.clone(), now_ms, "timeout");
 
     if vk == VK_BACK.0 as u32 {
         let mut buffer = state.text_buffer.lock().unwrap();
+        record_key_interval(state, &mut buffer, now_ms);
+        buffer.pending_high_surrogate = None;
+        buffer.backspaces += 1;
         if buffer.text.pop().is_some() {
             buffer.last_ts_ms = now_ms;
         }
         return;
     }
-    if vk == VK_RETURN.0 as u32 {
-        flush_text_buffer_with_window(state, window_info, "enter");
-        return;
-    }
-    if vk == VK_TAB.0 as u32 {
-        flush_text_buffer_with_window(state, window_info, "tab");
-        return;
-    }
-
-    let Some(text) = translate_vk_to_text(vk, scan_code) else {
-        return;
+    let units = if vk == VK_RETURN.0 as u32 || vk == VK_TAB.0 as u32 {
+        let (reason, separator) = if vk == VK_RETURN.0 as u32 { ("enter", "\n") } else { ("tab", "\t") };
+        if text_flush_allowed(state, reason) {
+            flush_text_buffer_with_window(state, window_info, reason);
+            return;
+        }
+        separator.encode_utf16().collect()
+    } else {
+        let Some(units) = translate_vk_to_utf16(vk, scan_code) else {
+            return;
+        };
+        units
     };
-    if text.is_empty() {
-        return;
-    }
     let should_flush = {
         let mut buffer = state.text_buffer.lock().unwrap();
+        let text = decode_key_units(&mut buffer.pending_high_surrogate, &units);
+        if text.is_empty() {
+            return;
+        }
+        record_key_interval(state, &mut buffer, now_ms);
         buffer.text.push_str(&text);
+        buffer.typed_chars += text.chars().count() as u32;
         buffer.last_ts_ms = now_ms;
         buffer.text.len() >= state.max_text_len
     };
@@ -2597,7 +5653,25 @@ fn handle_text_key(state: &RecorderState, window_info: Option<WindowInfo>, vk: u
     }
 }
 
-fn translate_vk_to_text(vk: u32, scan_code: u32) -> Option<String> {
+fn record_key_interval(state: &RecorderState, buffer: &mut TextBuffer, now_ms: i64) {
+    if state.capture_keystroke_timing && buffer.has_pending() {
+        buffer.key_intervals_ms.push(now_ms - buffer.last_ts_ms);
+    }
+}
+
+/// Prepends any held high surrogate and holds back a trailing one, so a pair split across two
+/// key events decodes to one character instead of two replacement characters.
+fn decode_key_units(pending: &mut Option<u16>, units: &[u16]) -> String {
+    let mut combined: Vec<u16> = pending.take().into_iter().chain(units.iter().copied()).collect();
+    if combined.last().is_some_and(|unit| (0xD800..0xDC00).contains(unit)) {
+        *pending = combined.pop();
+    }
+    char::decode_utf16(combined)
+        .map(|result| result.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
+}
+
+fn translate_vk_to_utf16(vk: u32, scan_code: u32) -> Option<Vec<u16>> {
     unsafe {
         let mut key_state = [0u8; 256];
         if GetKeyboardState(&mut key_state).is_err() {
@@ -2609,7 +5683,7 @@ fn translate_vk_to_text(vk: u32, scan_code: u32) -> Option<String> {
         if written <= 0 {
             return None;
         }
-        Some(String::from_utf16_lossy(&buffer[..written as usize]))
+        Some(buffer[..written as usize].to_vec())
     }
 }
 
@@ -2617,7 +5691,7 @@ fn flush_text_buffer_if_stale(state: &RecorderState, reason: &str) {
     let now_ms = now_mono_ms(state);
     let should_flush = {
         let buffer = state.text_buffer.lock().unwrap();
-        !buffer.text.is_empty() && now_ms - buffer.last_ts_ms >= state.text_flush_ms
+        buffer.has_pending() && now_ms - buffer.last_ts_ms >= state.text_flush_ms
     };
     if should_flush {
         flush_text_buffer(state, reason);
@@ -2632,7 +5706,7 @@ fn flush_text_buffer_if_stale_with_window(
 ) {
     let should_flush = {
         let buffer = state.text_buffer.lock().unwrap();
-        !buffer.text.is_empty() && now_ms - buffer.last_ts_ms >= state.text_flush_ms
+        buffer.has_pending() && now_ms - buffer.last_ts_ms >= state.text_flush_ms
     };
     if should_flush {
         flush_text_buffer_with_window(state, window_info, reason);
@@ -2644,15 +5718,27 @@ fn flush_text_buffer(state: &RecorderState, reason: &str) {
     flush_text_buffer_with_window(state, window_info, reason);
 }
 
+fn text_flush_allowed(state: &RecorderState, reason: &str) -> bool {
+    !OPTIONAL_TEXT_FLUSH_REASONS.contains(&reason) || state.text_flush_reasons.contains(reason)
+}
+
 fn flush_text_buffer_with_window(state: &RecorderState, window_info: Option<WindowInfo>, reason: &str) {
+    if !text_flush_allowed(state, reason) {
+        return;
+    }
     let now_ms = now_mono_ms(state);
-    let text = {
+    let (text, counts) = {
         let mut buffer = state.text_buffer.lock().unwrap();
-        if buffer.text.is_empty() {
+        if !buffer.has_pending() {
             return;
         }
         buffer.last_ts_ms = now_ms;
-        std::mem::take(&mut buffer.text)
+        let counts = TypingCounts {
+            typed_chars: std::mem::take(&mut buffer.typed_chars),
+            backspaces: std::mem::take(&mut buffer.backspaces),
+            key_intervals_ms: std::mem::take(&mut buffer.key_intervals_ms),
+        };
+        (std::mem::take(&mut buffer.text), counts)
     };
     let final_text = if state.text_snapshot_on_idle
         && matches!(reason, "idle_timeout" | "timeout")
@@ -2662,49 +5748,216 @@ fn flush_text_buffer_with_window(state: &RecorderState, window_info: Option<Wind
     } else {
         None
     };
-    send_text_event(state, window_info, text, reason, final_text);
+    send_text_event(state, window_info, text, counts, reason, final_text);
+}
+
+struct TypingCounts {
+    typed_chars: u32,
+    backspaces: u32,
+    key_intervals_ms: Vec<i64>,
 }
 
 fn send_text_event(
     state: &RecorderState,
     window_info: Option<WindowInfo>,
     text: String,
+    counts: TypingCounts,
     reason: &str,
     final_text: Option<String>,
 ) {
-    let (process_name, window_title, window_class, window_rect) = match window_info {
+    let (process_name, process_id, window_title, window_class, window_rect) = match window_info {
         Some(info) => (
             info.process_name.clone(),
+            info.process_id,
             Some(info.title.clone()),
             Some(info.class_name.clone()),
             info.rect.clone(),
         ),
-        None => (None, None, None, None),
+        None => (None, None, None, None, None),
     };
     let final_text = final_text.map(|text| truncate_text(text, state.max_text_len));
+    let diff = match final_text.as_ref() {
+        Some(value) if state.text_snapshot_diff => {
+            let key = format!(
+                "{}|{}",
+                process_name.as_deref().unwrap_or(""),
+                window_class.as_deref().unwrap_or("")
+            );
+            let previous = state
+                .last_final_text
+                .lock()
+                .unwrap()
+                .insert(key, value.text.clone());
+            previous.map(|previous| diff_snapshot_text(&previous, &value.text))
+        }
+        _ => None,
+    };
+    let mut payload = json!({
+        "text": text,
+        "reason": reason,
+        "typed_chars": counts.typed_chars,
+        "backspaces": counts.backspaces,
+        "final_text": final_text.as_ref().map(|value| value.text.clone()),
+        "final_text_length": final_text.as_ref().map(|value| value.length),
+        "final_text_truncated": final_text.as_ref().map(|value| value.truncated),
+        "source": if final_text.is_some() { "uia" } else { "buffer" },
+    });
+    if state.store_normalized_text {
+        if let Some(map) = payload.as_object_mut() {
+            map.insert("text_normalized".to_string(), json!(normalize_search_text(&text)));
+        }
+    }
+    if state.capture_keystroke_timing {
+        if let Some(map) = payload.as_object_mut() {
+            map.insert("key_intervals_ms".to_string(), json!(counts.key_intervals_ms));
+        }
+    }
+    if let (Some(diff), Some(map)) = (diff, payload.as_object_mut()) {
+        if diff.mode == "diff" {
+            map.insert("final_text".to_string(), Value::Null);
+        }
+        map.insert("final_text_mode".to_string(), json!(diff.mode));
+        map.insert("final_text_inserted".to_string(), json!(diff.inserted));
+        map.insert("final_text_removed_length".to_string(), json!(diff.removed_length));
+        map.insert("edit_distance".to_string(), json!(diff.edit_distance));
+    }
     let event = EventRecord {
         session_id: state.session_id.clone(),
         ts_wall_ms: now_wall_ms(),
         ts_mono_ms: now_mono_ms(state),
         event_type: "text_input".to_string(),
         process_name,
+        process_id,
         window_title,
         window_class,
         window_rect,
         mouse: None,
-        payload: json!({
-            "text": text,
-            "reason": reason,
-            "final_text": final_text.as_ref().map(|value| value.text.clone()),
-            "final_text_length": final_text.as_ref().map(|value| value.length),
-            "final_text_truncated": final_text.as_ref().map(|value| value.truncated),
-            "source": if final_text.is_some() { "uia" } else { "buffer" },
-        }),
+        payload,
+    };
+    emit_event(state, event);
+}
+
+/// Case-folded with whitespace runs collapsed to single spaces, so search can match it directly.
+fn normalize_search_text(text: &str) -> String {
+    text.split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+struct SnapshotDiff {
+    mode: &'static str,
+    inserted: String,
+    removed_length: usize,
+    edit_distance: Option<usize>,
+}
+
+/// Compares a UIA snapshot with the previous one for the same window. Text between the common
+/// prefix and suffix is the edit; when nothing is shared the snapshot is a full replace.
+fn diff_snapshot_text(previous: &str, current: &str) -> SnapshotDiff {
+    let old: Vec<char> = previous.chars().collect();
+    let new: Vec<char> = current.chars().collect();
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let inserted: String = new[prefix..new.len() - suffix].iter().collect();
+    let inserted_length = new.len() - prefix - suffix;
+    let removed_length = old.len() - prefix - suffix;
+    let mode = if prefix + suffix == 0 && !old.is_empty() { "replace" } else { "diff" };
+    // The DP is quadratic, so only run it on the differing middle and skip pathological sizes.
+    let edit_distance = if removed_length.saturating_mul(inserted_length) <= 4_000_000 {
+        Some(levenshtein(&old[prefix..old.len() - suffix], &new[prefix..new.len() - suffix]))
+    } else {
+        None
     };
-    state.sender.try_send(event).ok();
+    SnapshotDiff {
+        mode,
+        inserted,
+        removed_length,
+        edit_distance,
+    }
+}
+
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            current[j + 1] = (previous[j] + cost).min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// Mirrors events to TCP subscribers as newline-delimited JSON. The writer tees into a bounded
+/// channel with `try_send`, and clients that can't keep up are dropped on the first failed write,
+/// so neither side can stall the DB writer. The stream is unauthenticated, so only loopback
+/// addresses are accepted.
+fn spawn_live_stream(
+    addr: &str,
+    shutdown: Arc<AtomicBool>,
+) -> Result<(Sender<EventRecord>, thread::JoinHandle<()>)> {
+    let resolved: Vec<_> = addr
+        .to_socket_addrs()
+        .with_context(|| format!("Invalid live stream address {addr}"))?
+        .collect();
+    if resolved.is_empty() || resolved.iter().any(|socket| !socket.ip().is_loopback()) {
+        anyhow::bail!("live_stream_addr {addr} is not a loopback address");
+    }
+    let listener = TcpListener::bind(&resolved[..]).with_context(|| format!("Failed to bind live stream on {addr}"))?;
+    listener.set_nonblocking(true)?;
+    log_line(&format!("Live stream listening on {addr}"));
+    let (live_tx, live_rx) = bounded::<EventRecord>(LIVE_STREAM_CAPACITY);
+    let handle = thread::spawn(move || {
+        let mut clients: Vec<TcpStream> = Vec::new();
+        loop {
+            while let Ok((stream, peer)) = listener.accept() {
+                if stream.set_nonblocking(false).is_ok()
+                    && stream.set_write_timeout(Some(Duration::from_millis(200))).is_ok()
+                {
+                    let _ = stream.set_nodelay(true);
+                    log_line(&format!("Live stream client connected: {peer}"));
+                    clients.push(stream);
+                }
+            }
+            match live_rx.recv_timeout(Duration::from_millis(100)) {
+                Ok(event) => {
+                    if clients.is_empty() {
+                        continue;
+                    }
+                    let Ok(mut line) = serde_json::to_vec(&event) else {
+                        continue;
+                    };
+                    line.push(b'\n');
+                    clients.retain_mut(|client| client.write_all(&line).is_ok());
+                }
+                Err(crossbeam_channel::RecvTimeoutError::Timeout) => {
+                    if shutdown.load(Ordering::SeqCst) {
+                        break;
+                    }
+                }
+                Err(crossbeam_channel::RecvTimeoutError::Disconnected) => break,
+            }
+        }
+    });
+    Ok((live_tx, handle))
 }
 
-fn run_writer(rx: Receiver<EventRecord>, db_path: &Path, session: SessionInfo, shutdown: Arc<AtomicBool>) {
+fn run_writer(
+    rx: Receiver<QueuedEvent>,
+    db_path: &Path,
+    session: SessionInfo,
+    shutdown: Arc<AtomicBool>,
+    queue_near_full: Arc<AtomicBool>,
+    live_tx: Option<Sender<EventRecord>>,
+) {
     let mut conn = match Connection::open(db_path) {
         Ok(conn) => conn,
         Err(err) => {
@@ -2721,11 +5974,20 @@ fn run_writer(rx: Receiver<EventRecord>, db_path: &Path, session: SessionInfo, s
         return;
     }
 
-    let mut buffer: Vec<EventRecord> = Vec::with_capacity(200);
+    let mut buffer: Vec<QueuedEvent> = Vec::with_capacity(200);
     let flush_interval = Duration::from_millis(250);
     loop {
+        let queue_len = rx.len();
+        if queue_len >= QUEUE_HIGH_WATER {
+            queue_near_full.store(true, Ordering::Relaxed);
+        } else if queue_len <= QUEUE_LOW_WATER {
+            queue_near_full.store(false, Ordering::Relaxed);
+        }
         match rx.recv_timeout(flush_interval) {
             Ok(event) => {
+                if let Some(live_tx) = live_tx.as_ref() {
+                    live_tx.try_send(event.event.clone()).ok();
+                }
                 buffer.push(event);
                 if buffer.len() >= 200 {
                     if let Err(err) = flush_events(&mut conn, &buffer) {
@@ -2760,7 +6022,8 @@ fn init_db(conn: &Connection) -> Result<()> {
             session_id TEXT UNIQUE,
             start_wall_ms INTEGER,
             start_wall_iso TEXT,
-            obs_video_path TEXT
+            obs_video_path TEXT,
+            schema_version INTEGER
         );
         CREATE TABLE IF NOT EXISTS events (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -2769,6 +6032,7 @@ fn init_db(conn: &Connection) -> Result<()> {
             ts_mono_ms INTEGER,
             event_type TEXT,
             process_name TEXT,
+            process_id INTEGER,
             window_title TEXT,
             window_class TEXT,
             window_rect TEXT,
@@ -2778,43 +6042,67 @@ fn init_db(conn: &Connection) -> Result<()> {
         );
         CREATE INDEX IF NOT EXISTS idx_events_session_time ON events(session_id, ts_mono_ms);
         CREATE INDEX IF NOT EXISTS idx_events_session_type ON events(session_id, event_type);
+        CREATE TABLE IF NOT EXISTS typing_stats (
+            process_name TEXT NOT NULL,
+            hour_start_ms INTEGER NOT NULL,
+            chars_typed INTEGER NOT NULL DEFAULT 0,
+            backspaces INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (process_name, hour_start_ms)
+        );
+        CREATE TABLE IF NOT EXISTS session_tags (
+            session_id TEXT NOT NULL,
+            tag TEXT NOT NULL,
+            PRIMARY KEY (session_id, tag)
+        );
         ",
     )?;
+    ensure_column(conn, "sessions", "schema_version", "INTEGER")?;
+    ensure_column(conn, "events", "process_id", "INTEGER")?;
+    Ok(())
+}
+
+fn ensure_column(conn: &Connection, table: &str, column: &str, decl: &str) -> Result<()> {
+    let columns = table_columns(conn, table)?;
+    if !columns.iter().any(|name| name == column) {
+        conn.execute_batch(&format!("ALTER TABLE {table} ADD COLUMN {column} {decl}"))?;
+    }
     Ok(())
 }
 
 fn insert_session(conn: &Connection, session: &SessionInfo) -> Result<()> {
     conn.execute(
-        "INSERT INTO sessions (session_id, start_wall_ms, start_wall_iso, obs_video_path) VALUES (?, ?, ?, ?)",
+        "INSERT OR IGNORE INTO sessions (session_id, start_wall_ms, start_wall_iso, obs_video_path, schema_version)
+         VALUES (?, ?, ?, ?, ?)",
         params![
             session.session_id,
             session.start_wall_ms,
             session.start_wall_iso,
-            session.obs_video_path
+            session.obs_video_path,
+            session.schema_version
         ],
     )?;
     Ok(())
 }
 
-fn flush_events(conn: &mut Connection, events: &[EventRecord]) -> Result<()> {
+fn flush_events(conn: &mut Connection, events: &[QueuedEvent]) -> Result<()> {
     let tx = conn.transaction()?;
     {
         let mut stmt = tx.prepare(
             "INSERT INTO events (
-                session_id, ts_wall_ms, ts_mono_ms, event_type, process_name, window_title, window_class, window_rect, mouse, payload
-             ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                session_id, ts_wall_ms, ts_mono_ms, event_type, process_name, process_id, window_title, window_class, window_rect, mouse, payload
+             ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
         )?;
 
-        for event in events {
-            let window_rect = event
-                .window_rect
-                .as_ref()
-                .and_then(|rect| serde_json::to_string(rect).ok());
-            let mouse = event
-                .mouse
-                .as_ref()
-                .and_then(|m| serde_json::to_string(m).ok());
-            let payload = serde_json::to_string(&event.payload).unwrap_or_else(|_| "{}".to_string());
+        for queued in events {
+            let event = &queued.event;
+            let serialized;
+            let columns = match queued.columns.as_ref() {
+                Some(columns) => columns,
+                None => {
+                    serialized = SerializedColumns::from_event(event);
+                    &serialized
+                }
+            };
 
             stmt.execute(params![
                 event.session_id,
@@ -2822,11 +6110,35 @@ fn flush_events(conn: &mut Connection, events: &[EventRecord]) -> Result<()> {
                 event.ts_mono_ms,
                 event.event_type,
                 event.process_name,
+                event.process_id,
                 event.window_title,
                 event.window_class,
-                window_rect,
-                mouse,
-                payload,
+                columns.window_rect,
+                columns.mouse,
+                columns.payload,
+            ])?;
+        }
+
+        let mut stats_stmt = tx.prepare(
+            "INSERT INTO typing_stats (process_name, hour_start_ms, chars_typed, backspaces)
+             VALUES (?, ?, ?, ?)
+             ON CONFLICT(process_name, hour_start_ms) DO UPDATE SET
+                chars_typed = chars_typed + excluded.chars_typed,
+                backspaces = backspaces + excluded.backspaces",
+        )?;
+        for (event, (typed_chars, backspaces)) in events
+            .iter()
+            .filter_map(|queued| queued.typing_counts.map(|counts| (&queued.event, counts)))
+        {
+            if typed_chars == 0 && backspaces == 0 {
+                continue;
+            }
+            let hour_start_ms = event.ts_wall_ms - event.ts_wall_ms.rem_euclid(3_600_000);
+            stats_stmt.execute(params![
+                event.process_name.as_deref().unwrap_or(""),
+                hour_start_ms,
+                typed_chars,
+                backspaces,
             ])?;
         }
     }
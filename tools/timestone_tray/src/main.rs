@@ -18,27 +18,31 @@ use windows::Win32::System::Threading::{
     GetExitCodeProcess, OpenProcess, CREATE_NO_WINDOW, PROCESS_QUERY_LIMITED_INFORMATION,
 };
 use windows::Win32::UI::Shell::{
-    Shell_NotifyIconW, NOTIFYICONDATAW, NIF_ICON, NIF_MESSAGE, NIF_TIP, NIM_ADD, NIM_DELETE, NIM_MODIFY,
+    ShellExecuteW, Shell_NotifyIconW, NOTIFYICONDATAW, NIF_ICON, NIF_MESSAGE, NIF_TIP, NIM_ADD, NIM_DELETE, NIM_MODIFY,
 };
 use windows::Win32::UI::WindowsAndMessaging::{
     AppendMenuW, CreatePopupMenu, CreateWindowExW, DefWindowProcW, DestroyWindow, DispatchMessageW, GetCursorPos,
     GetMessageW, LoadIconW, LoadImageW, MessageBoxW, PostQuitMessage, RegisterClassW, SetForegroundWindow, SetTimer,
     TrackPopupMenu, TranslateMessage, CW_USEDEFAULT, HICON, HMENU, IMAGE_ICON, LR_DEFAULTSIZE, LR_LOADFROMFILE, MB_OK,
-    MF_CHECKED, MF_GRAYED, MF_POPUP, MF_SEPARATOR, MF_STRING, TPM_LEFTALIGN, TPM_TOPALIGN, WM_COMMAND, WM_DESTROY,
+    MF_CHECKED, MF_GRAYED, MF_POPUP, MF_SEPARATOR, MF_STRING, SW_SHOWNORMAL, TPM_LEFTALIGN, TPM_TOPALIGN, WM_COMMAND, WM_DESTROY,
     WM_LBUTTONUP, WM_RBUTTONUP, WM_NULL, WM_TIMER, WM_USER, WNDCLASSW, WS_OVERLAPPEDWINDOW,
 };
 
 const APP_DIR: &str = "data\\timestone";
+const DATA_DIR_ENV: &str = "TIMESTONE_DATA_DIR";
 const TRAY_CONFIG_FILE: &str = "tray_config.json";
 const TRAY_ICON_CACHE: &str = "tray_icons";
 const RECORDER_CONFIG_FILE: &str = "config.json";
 const LOG_DIR: &str = "logs";
 const TRAY_LOG_FILE: &str = "tray.log";
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+const LOG_GENERATIONS: u32 = 3;
 
 const TRAY_ICON_ID: u32 = 1;
 const WM_TRAY: u32 = WM_USER + 1;
 const TIMER_ID: usize = 1;
 const TIMER_MS: u32 = 5000;
+const AUTO_RESTART_COOLDOWN_MS: u64 = 60_000;
 
 const CMD_START: u16 = 1001;
 const CMD_PAUSE: u16 = 1002;
@@ -46,6 +50,8 @@ const CMD_RESUME: u16 = 1003;
 const CMD_STOP: u16 = 1004;
 const CMD_STATUS: u16 = 1005;
 const CMD_EXIT: u16 = 1006;
+const CMD_OPEN_DATA: u16 = 1007;
+const CMD_OPEN_LOG: u16 = 1008;
 const CMD_SETTINGS_CLICK: u16 = 1101;
 const CMD_SETTINGS_SCROLL: u16 = 1102;
 const CMD_SETTINGS_BOTH: u16 = 1103;
@@ -57,8 +63,10 @@ struct TrayConfig {
     icon_paused: Option<String>,
     icon_stopped: Option<String>,
     tooltip: Option<String>,
+    tooltip_template: Option<String>,
     recorder_exe: Option<String>,
     recorder_args: Option<Vec<String>>,
+    auto_restart: bool,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -77,6 +85,7 @@ struct RecorderCommand {
 struct AppState {
     hwnd: HWND,
     tooltip: String,
+    tooltip_template: Option<String>,
     command: RecorderCommand,
     data_dir: PathBuf,
     icon_running: HICON,
@@ -84,6 +93,12 @@ struct AppState {
     icon_stopped: HICON,
     status: RecorderStatus,
     busy: bool,
+    auto_restart: bool,
+    /// Set by a tray-issued stop so the status poll can tell it apart from a crash.
+    stop_requested: bool,
+    /// An unexpected stop seen during the cooldown; retried on a later poll.
+    restart_pending: bool,
+    last_auto_restart: Option<std::time::Instant>,
 }
 
 static STATE: OnceLock<Arc<Mutex<AppState>>> = OnceLock::new();
@@ -105,6 +120,7 @@ fn log_line(data_dir: &Path, message: &str) {
     let log_dir = data_dir.join(LOG_DIR);
     let path = log_dir.join(TRAY_LOG_FILE);
     let _ = fs::create_dir_all(&log_dir);
+    rotate_log_if_needed(&log_dir, &path);
     let ts = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap_or_default()
@@ -114,8 +130,32 @@ fn log_line(data_dir: &Path, message: &str) {
     }
 }
 
+/// Shifts tray.log to tray.1.log (and older generations up by one) once it passes MAX_LOG_BYTES.
+/// Errors are ignored so a locked or missing file never stops the write that follows.
+fn rotate_log_if_needed(log_dir: &Path, path: &Path) {
+    let size = match fs::metadata(path) {
+        Ok(meta) => meta.len(),
+        Err(_) => return,
+    };
+    if size < MAX_LOG_BYTES {
+        return;
+    }
+    let (stem, extension) = TRAY_LOG_FILE.rsplit_once('.').unwrap_or((TRAY_LOG_FILE, "log"));
+    let generation = |index: u32| log_dir.join(format!("{stem}.{index}.{extension}"));
+    let _ = fs::remove_file(generation(LOG_GENERATIONS));
+    for index in (1..LOG_GENERATIONS).rev() {
+        let _ = fs::rename(generation(index), generation(index + 1));
+    }
+    let _ = fs::rename(path, generation(1));
+}
+
 fn main() -> Result<()> {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    apply_data_dir_arg(&mut args);
     let base_dir = ensure_app_dir()?;
+    if args.first().map(String::as_str) == Some("validate-config") {
+        return validate_configs(&base_dir);
+    }
     let config = load_or_create_config(&base_dir)?;
     let command = build_command(&config);
     let data_dir = base_dir.clone();
@@ -156,6 +196,7 @@ fn main() -> Result<()> {
         let state = AppState {
             hwnd,
             tooltip,
+            tooltip_template: config.tooltip_template.clone().filter(|template| !template.trim().is_empty()),
             command,
             data_dir,
             icon_running,
@@ -163,6 +204,10 @@ fn main() -> Result<()> {
             icon_stopped,
             status,
             busy: false,
+            auto_restart: config.auto_restart,
+            stop_requested: false,
+            restart_pending: false,
+            last_auto_restart: None,
         };
         let shared = Arc::new(Mutex::new(state));
         let _ = STATE.set(shared);
@@ -182,21 +227,49 @@ fn main() -> Result<()> {
     Ok(())
 }
 
+/// Uses `TIMESTONE_DATA_DIR` when set, otherwise `data\timestone` under the current directory.
 fn ensure_app_dir() -> Result<PathBuf> {
-    let cwd = env::current_dir().context("Failed to read current dir")?;
-    let base_dir = cwd.join(APP_DIR);
+    let base_dir = match env::var_os(DATA_DIR_ENV) {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => env::current_dir().context("Failed to read current dir")?.join(APP_DIR),
+    };
     if !base_dir.exists() {
         fs::create_dir_all(&base_dir).context("Failed to create timestone data dir")?;
     }
     Ok(base_dir)
 }
 
+/// Strips a global `--data-dir PATH` from the arguments and exports it as `TIMESTONE_DATA_DIR`,
+/// which `ensure_app_dir` reads and any child process inherits.
+fn apply_data_dir_arg(args: &mut Vec<String>) {
+    let Some(index) = args.iter().position(|arg| arg == "--data-dir") else {
+        return;
+    };
+    args.remove(index);
+    if index < args.len() {
+        env::set_var(DATA_DIR_ENV, args.remove(index));
+    }
+}
+
 fn load_or_create_config(base_dir: &Path) -> Result<TrayConfig> {
     let path = base_dir.join(TRAY_CONFIG_FILE);
     if path.exists() {
         let contents = fs::read_to_string(&path).context("Failed to read tray config")?;
-        let config: TrayConfig = serde_json::from_str(&contents).context("Failed to parse tray config")?;
-        return Ok(config);
+        return match serde_json::from_str::<TrayConfig>(&contents) {
+            Ok(config) => Ok(config),
+            Err(err) => {
+                let backup_path = path.with_extension("json.bak");
+                let _ = fs::copy(&path, &backup_path);
+                log_line(
+                    base_dir,
+                    &format!(
+                        "Tray config unreadable ({err}); using defaults. Original saved to {}.",
+                        backup_path.display()
+                    ),
+                );
+                Ok(TrayConfig::default())
+            }
+        };
     }
     let config = TrayConfig::default();
     let payload = serde_json::to_string_pretty(&config).context("Failed to serialize tray config")?;
@@ -204,6 +277,43 @@ fn load_or_create_config(base_dir: &Path) -> Result<TrayConfig> {
     Ok(config)
 }
 
+/// Checks tray_config.json and the recorder's config.json without starting the tray, printing the
+/// parsed tray config on success and serde_json's line/column on failure.
+fn validate_configs(base_dir: &Path) -> Result<()> {
+    let mut invalid = 0;
+    let tray_path = base_dir.join(TRAY_CONFIG_FILE);
+    match fs::read_to_string(&tray_path) {
+        Ok(contents) => match serde_json::from_str::<TrayConfig>(&contents) {
+            Ok(config) => {
+                println!("{}: ok", tray_path.display());
+                println!("{}", serde_json::to_string_pretty(&config)?);
+            }
+            Err(err) => {
+                invalid += 1;
+                println!(
+                    "{}: line {}, column {}: {}",
+                    tray_path.display(),
+                    err.line(),
+                    err.column(),
+                    err
+                );
+            }
+        },
+        Err(_) => println!("{}: missing (defaults will be written on start)", tray_path.display()),
+    }
+    match read_recorder_config(base_dir) {
+        Ok(_) => println!("{}: ok", recorder_config_path(base_dir).display()),
+        Err(err) => {
+            invalid += 1;
+            println!("{err:#}");
+        }
+    }
+    if invalid > 0 {
+        anyhow::bail!("{invalid} config file(s) failed to parse");
+    }
+    Ok(())
+}
+
 fn build_command(config: &TrayConfig) -> RecorderCommand {
     if let Some(exe) = config.recorder_exe.clone() {
         let args_prefix = config.recorder_args.clone().unwrap_or_default();
@@ -231,12 +341,26 @@ fn recorder_config_path(data_dir: &Path) -> PathBuf {
 }
 
 fn load_recorder_config(data_dir: &Path) -> Value {
+    read_recorder_config(data_dir).unwrap_or_else(|_| Value::Object(Default::default()))
+}
+
+/// A missing file reads as an empty object, but a parse failure is an error so the settings
+/// writers below never replace a hand-edited config that merely has a typo.
+fn read_recorder_config(data_dir: &Path) -> Result<Value> {
     let path = recorder_config_path(data_dir);
-    let contents = match fs::read_to_string(path) {
+    let contents = match fs::read_to_string(&path) {
         Ok(contents) => contents,
-        Err(_) => return Value::Object(Default::default()),
+        Err(_) => return Ok(Value::Object(Default::default())),
     };
-    serde_json::from_str(&contents).unwrap_or_else(|_| Value::Object(Default::default()))
+    serde_json::from_str(&contents).map_err(|err| {
+        anyhow::anyhow!(
+            "{} is not valid JSON (line {}, column {}): {}",
+            path.display(),
+            err.line(),
+            err.column(),
+            err
+        )
+    })
 }
 
 fn write_recorder_config(data_dir: &Path, config: &Value) -> Result<()> {
@@ -255,7 +379,7 @@ fn get_recorder_flag(data_dir: &Path, key: &str, default: bool) -> bool {
 }
 
 fn set_recorder_flag(data_dir: &Path, key: &str, value: bool) -> Result<()> {
-    let mut config = load_recorder_config(data_dir);
+    let mut config = read_recorder_config(data_dir)?;
     if !config.is_object() {
         config = Value::Object(Default::default());
     }
@@ -266,7 +390,7 @@ fn set_recorder_flag(data_dir: &Path, key: &str, value: bool) -> Result<()> {
 }
 
 fn set_recorder_flags(data_dir: &Path, click: bool, scroll: bool) -> Result<()> {
-    let mut config = load_recorder_config(data_dir);
+    let mut config = read_recorder_config(data_dir)?;
     if !config.is_object() {
         config = Value::Object(Default::default());
     }
@@ -277,6 +401,17 @@ fn set_recorder_flags(data_dir: &Path, click: bool, scroll: bool) -> Result<()>
     write_recorder_config(data_dir, &config)
 }
 
+fn finish_settings_change(data_dir: &Path, result: Result<()>) {
+    match result {
+        Ok(()) => write_reload_signal(data_dir),
+        Err(err) => {
+            log_line(data_dir, &format!("Settings not saved: {err:#}"));
+            let hwnd = STATE.get().map(|state| state.lock().unwrap().hwnd).unwrap_or(HWND(0));
+            show_message(hwnd, &format!("Settings not saved.\n\n{err:#}"));
+        }
+    }
+}
+
 fn write_reload_signal(data_dir: &Path) {
     let path = data_dir.join("reload_config.signal");
     let _ = fs::write(path, b"reload");
@@ -366,10 +501,10 @@ fn update_tray_icon() -> Result<()> {
         RecorderStatus::Paused => "paused",
         RecorderStatus::Stopped => "stopped",
     };
-    let tooltip = if state.busy {
-        format!("{} ({}, busy)", state.tooltip, status)
-    } else {
-        format!("{} ({})", state.tooltip, status)
+    let tooltip = match state.tooltip_template.as_deref() {
+        Some(template) => render_tooltip(template, &state, status),
+        None if state.busy => format!("{} ({}, busy)", state.tooltip, status),
+        None => format!("{} ({})", state.tooltip, status),
     };
     let mut data = tray_data(state.hwnd, icon, &tooltip);
     let ok = unsafe { Shell_NotifyIconW(NIM_MODIFY, &mut data) };
@@ -385,6 +520,25 @@ fn update_tray_icon() -> Result<()> {
     }
 }
 
+/// Fills {tooltip}, {status}, {busy} and {session} (short session id from the lock file).
+/// Unknown placeholders are left as-is; tray_data truncates the result to the 127-char tip.
+fn render_tooltip(template: &str, state: &AppState, status: &str) -> String {
+    let session = if state.status == RecorderStatus::Stopped {
+        String::new()
+    } else {
+        read_lock_value(&state.data_dir.join("recorder.lock"), "session_id")
+            .map(|id| id.chars().take(8).collect())
+            .unwrap_or_default()
+    };
+    template
+        .replace("{tooltip}", &state.tooltip)
+        .replace("{status}", status)
+        .replace("{busy}", if state.busy { "busy" } else { "" })
+        .replace("{session}", &session)
+        .trim()
+        .to_string()
+}
+
 fn cleanup_tray_icon() {
     if let Some(state) = STATE.get() {
         let state = state.lock().unwrap();
@@ -419,7 +573,7 @@ fn to_wide(value: &str) -> Vec<u16> {
 fn run_command(command: &RecorderCommand, action: &str) -> Result<String> {
     let mut cmd = Command::new(&command.exe);
     cmd.args(&command.args_prefix);
-    cmd.arg(action);
+    cmd.args(action.split_whitespace());
     cmd.creation_flags(CREATE_NO_WINDOW.0);
     cmd.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped());
     let output = cmd.output().context("Failed to run recorder command")?;
@@ -442,7 +596,14 @@ fn run_command_async(command: &RecorderCommand, action: &str) -> Result<()> {
 }
 
 fn get_status(command: &RecorderCommand) -> Result<RecorderStatus> {
-    let output = run_command(command, "status").unwrap_or_default();
+    let output = run_command(command, "status --json").unwrap_or_default();
+    if let Ok(status) = serde_json::from_str::<Value>(output.trim()) {
+        return Ok(match status.get("status").and_then(|value| value.as_str()) {
+            Some("paused") => RecorderStatus::Paused,
+            Some("running") => RecorderStatus::Running,
+            _ => RecorderStatus::Stopped,
+        });
+    }
     if output.contains("Recorder status: paused") {
         Ok(RecorderStatus::Paused)
     } else if output.contains("Recorder status: running") {
@@ -500,15 +661,17 @@ fn wait_for_lock_clear(data_dir: &Path, timeout_ms: u64) -> bool {
 }
 
 fn read_lock_pid(lock_path: &Path) -> Option<u32> {
+    read_lock_value(lock_path, "pid")?.parse::<u32>().ok()
+}
+
+fn read_lock_value(lock_path: &Path, wanted: &str) -> Option<String> {
     let contents = fs::read_to_string(lock_path).ok()?;
     for line in contents.lines() {
         let mut parts = line.splitn(2, '=');
         let key = parts.next().unwrap_or("").trim();
         let value = parts.next().unwrap_or("").trim();
-        if key == "pid" {
-            if let Ok(pid) = value.parse::<u32>() {
-                return Some(pid);
-            }
+        if key == wanted && !value.is_empty() {
+            return Some(value.to_string());
         }
     }
     None
@@ -532,17 +695,52 @@ fn is_pid_running(pid: u32) -> bool {
 }
 
 fn update_status() {
-    if let Some(state) = STATE.get() {
-        let mut state = state.lock().unwrap();
+    let Some(shared) = STATE.get() else {
+        return;
+    };
+    let (changed, restart) = {
+        let mut state = shared.lock().unwrap();
+        // A recorder that exits cleanly removes its lock, so a lock whose pid is gone means it
+        // died; get_status_from_files clears that lock, so look before polling.
+        let crashed = lock_is_stale(&state.data_dir);
         let status = get_status_from_files(&state.data_dir);
-        if status != state.status {
+        let changed = status != state.status;
+        if changed {
             state.status = status;
             log_line(&state.data_dir, &format!("status poll changed: {status:?}"));
-            let _ = update_tray_icon();
         }
+        if crashed && state.auto_restart && !state.stop_requested {
+            log_line(&state.data_dir, "recorder stopped unexpectedly (stale lock)");
+            state.restart_pending = true;
+        }
+        if status != RecorderStatus::Stopped {
+            state.restart_pending = false;
+        }
+        let cooling_down = state
+            .last_auto_restart
+            .is_some_and(|last| last.elapsed() < Duration::from_millis(AUTO_RESTART_COOLDOWN_MS));
+        let restart = state.restart_pending && !state.busy && !cooling_down;
+        if restart {
+            log_line(&state.data_dir, "auto-restarting recorder");
+            state.restart_pending = false;
+            state.last_auto_restart = Some(std::time::Instant::now());
+        } else if crashed && state.restart_pending {
+            log_line(&state.data_dir, "auto-restart cooling down; will retry");
+        }
+        (changed, restart)
+    };
+    if changed {
+        let _ = update_tray_icon();
+    }
+    if restart {
+        dispatch_command("start", false);
     }
 }
 
+fn lock_is_stale(data_dir: &Path) -> bool {
+    read_lock_pid(&data_dir.join("recorder.lock")).is_some_and(|pid| !is_pid_running(pid))
+}
+
 fn dispatch_command(action: &str, show_dialog: bool) {
     let Some(state) = STATE.get() else {
         return;
@@ -554,6 +752,11 @@ fn dispatch_command(action: &str, show_dialog: bool) {
             return;
         }
         state.busy = true;
+        match action {
+            "stop" => state.stop_requested = true,
+            "start" => state.stop_requested = false,
+            _ => {}
+        }
     }
     let _ = update_tray_icon();
     let state = state.clone();
@@ -641,8 +844,11 @@ fn show_status_dialog(hwnd: HWND, status: RecorderStatus) {
         RecorderStatus::Paused => "paused",
         RecorderStatus::Stopped => "stopped",
     };
-    let message = format!("Recorder status: {status}");
-    let wide = to_wide(&message);
+    show_message(hwnd, &format!("Recorder status: {status}"));
+}
+
+fn show_message(hwnd: HWND, message: &str) {
+    let wide = to_wide(message);
     unsafe {
         let _ = MessageBoxW(hwnd, PCWSTR(wide.as_ptr()), PCWSTR(to_wide("Timestone").as_ptr()), MB_OK);
     }
@@ -698,7 +904,7 @@ fn handle_left_click() {
 fn handle_menu_command(cmd: u16) {
     if let Some(state) = STATE.get() {
         let state = state.lock().unwrap();
-        if state.busy && cmd != CMD_STATUS {
+        if state.busy && !matches!(cmd, CMD_STATUS | CMD_OPEN_DATA | CMD_OPEN_LOG) {
             return;
         }
     }
@@ -721,6 +927,24 @@ fn handle_menu_command(cmd: u16) {
                 show_status_dialog(hwnd, status);
             }
         }
+        CMD_OPEN_DATA => {
+            if let Some(state) = STATE.get() {
+                let data_dir = state.lock().unwrap().data_dir.clone();
+                shell_open(&data_dir);
+            }
+        }
+        CMD_OPEN_LOG => {
+            if let Some(state) = STATE.get() {
+                let (data_dir, hwnd) = {
+                    let state = state.lock().unwrap();
+                    (state.data_dir.clone(), state.hwnd)
+                };
+                match newest_log_file(&data_dir) {
+                    Some(path) => shell_open(&path),
+                    None => show_message(hwnd, "No log files found."),
+                }
+            }
+        }
         CMD_EXIT => {
             dispatch_command("stop", false);
             unsafe {
@@ -731,29 +955,59 @@ fn handle_menu_command(cmd: u16) {
             if let Some(state) = STATE.get() {
                 let data_dir = state.lock().unwrap().data_dir.clone();
                 let enabled = get_recorder_flag(&data_dir, "emit_mouse_click", true);
-                let _ = set_recorder_flag(&data_dir, "emit_mouse_click", !enabled);
-                write_reload_signal(&data_dir);
+                let result = set_recorder_flag(&data_dir, "emit_mouse_click", !enabled);
+                finish_settings_change(&data_dir, result);
             }
         }
         CMD_SETTINGS_SCROLL => {
             if let Some(state) = STATE.get() {
                 let data_dir = state.lock().unwrap().data_dir.clone();
                 let enabled = get_recorder_flag(&data_dir, "emit_mouse_scroll", false);
-                let _ = set_recorder_flag(&data_dir, "emit_mouse_scroll", !enabled);
-                write_reload_signal(&data_dir);
+                let result = set_recorder_flag(&data_dir, "emit_mouse_scroll", !enabled);
+                finish_settings_change(&data_dir, result);
             }
         }
         CMD_SETTINGS_BOTH => {
             if let Some(state) = STATE.get() {
                 let data_dir = state.lock().unwrap().data_dir.clone();
-                let _ = set_recorder_flags(&data_dir, true, true);
-                write_reload_signal(&data_dir);
+                let result = set_recorder_flags(&data_dir, true, true);
+                finish_settings_change(&data_dir, result);
             }
         }
         _ => {}
     }
 }
 
+fn newest_log_file(data_dir: &Path) -> Option<PathBuf> {
+    fs::read_dir(data_dir.join(LOG_DIR))
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let meta = entry.metadata().ok()?;
+            if !meta.is_file() {
+                return None;
+            }
+            Some((meta.modified().ok()?, entry.path()))
+        })
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path)
+}
+
+fn shell_open(path: &Path) {
+    let verb = to_wide("open");
+    let target = to_wide(&path.to_string_lossy());
+    unsafe {
+        ShellExecuteW(
+            HWND(0),
+            PCWSTR(verb.as_ptr()),
+            PCWSTR(target.as_ptr()),
+            PCWSTR::null(),
+            PCWSTR::null(),
+            SW_SHOWNORMAL,
+        );
+    }
+}
+
 fn show_menu(hwnd: HWND) {
     unsafe {
         let menu = match CreatePopupMenu() {
@@ -791,6 +1045,9 @@ fn show_menu(hwnd: HWND) {
         append_item(menu, CMD_STOP, "Stop", status == RecorderStatus::Stopped);
         append_item(menu, CMD_STATUS, "Status", false);
         let _ = AppendMenuW(menu, MF_SEPARATOR, 0, PCWSTR::null());
+        append_item(menu, CMD_OPEN_DATA, "Open Data Folder", false);
+        append_item(menu, CMD_OPEN_LOG, "Open Log", false);
+        let _ = AppendMenuW(menu, MF_SEPARATOR, 0, PCWSTR::null());
         append_item(menu, CMD_EXIT, "Exit", false);
 
         let mut pt = POINT::default();